@@ -0,0 +1,11 @@
+// build.rs
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use a system protoc when PROTOC is set, otherwise the vendored binary.
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+    println!("cargo:rerun-if-changed=proto/oracle.proto");
+    println!("cargo:rerun-if-changed=migrations");
+    tonic_build::configure().compile_protos(&["proto/oracle.proto"], &["proto"])?;
+    Ok(())
+}
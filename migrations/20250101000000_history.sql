@@ -0,0 +1,38 @@
+-- Mark, funding and raw quote history.
+-- Timestamps: *_ts_ms / ts_ms are unix ms from the oracle; recorded_at is when the row was written.
+
+CREATE TABLE IF NOT EXISTS index_ticks (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    symbol        TEXT    NOT NULL,
+    price         REAL    NOT NULL,
+    price_scaled  INTEGER,            -- NULL when expo is not supported by scale_by_expo
+    expo          INTEGER NOT NULL,
+    ts_ms         INTEGER NOT NULL,
+    source        TEXT    NOT NULL,
+    window_sec    INTEGER NOT NULL,
+    signature     TEXT,               -- hex r||s, when signing is enabled
+    recovery_id   INTEGER,
+    signer        TEXT,
+    recorded_at   TEXT    NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_index_ticks_symbol_ts ON index_ticks (symbol, ts_ms);
+
+CREATE TABLE IF NOT EXISTS funding_updates (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    symbol        TEXT    NOT NULL,   -- as published, e.g. "LEAN_HOGS_PERP-PERP"
+    rate          REAL    NOT NULL,
+    interval_sec  INTEGER NOT NULL,
+    ts_ms         INTEGER NOT NULL,
+    recorded_at   TEXT    NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_funding_updates_symbol_ts ON funding_updates (symbol, ts_ms);
+
+CREATE TABLE IF NOT EXISTS cfd_quotes (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    symbol        TEXT    NOT NULL,
+    provider      TEXT    NOT NULL,
+    price         REAL    NOT NULL,
+    ts_ms         INTEGER NOT NULL,
+    recorded_at   TEXT    NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_cfd_quotes_symbol_ts ON cfd_quotes (symbol, ts_ms);
@@ -0,0 +1,341 @@
+// src/audit.rs
+//! Per-tick audit trail.
+//!
+//! `Oracle::tick_once` returns a `TickReport` covering one pass of the pipeline:
+//! every quote it saw and what happened to it, the filter parameters in force,
+//! the mark before and after the step clamp, the breaker state, the perp
+//! premium, the funding interval, provider reputation, the result of every
+//! publish and the updates sink publish policies held back. `AuditLog` appends
+//! reports to a file as NDJSON (one report per line); existing lines are never
+//! rewritten. `query` reads them back.
+
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::funding::Settlement;
+use crate::health::{HealthStatus, OracleHealth};
+use crate::publishing::policy::Suppressed;
+use crate::reputation::{ProviderScore, ReputationEvent};
+use crate::types::{CfdQuote, ConsensusStats, ImpactPrices};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteVerdict {
+    Kept,        // used in the consensus
+    Outlier,     // outside the MAD band
+    Stale,       // older than `max_staleness_ms`
+    Invalid,     // non-finite or non-positive price
+    Late,        // missed a previous tick's deadline; recorded only
+    Unused,      // fresh, but the tick ended before consensus
+    Quarantined, // fresh, but the provider is quarantined; scored only
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuoteRecord {
+    pub provider: String,
+    pub price: f64,
+    pub ts_ms: i64,
+    pub age_ms: i64, // at the time of the staleness check
+    pub weight: f64, // consensus weight; 0 unless kept
+    pub verdict: QuoteVerdict,
+}
+
+impl QuoteRecord {
+    pub fn new(q: &CfdQuote, now_ms: i64, verdict: QuoteVerdict, weight: f64) -> Self {
+        Self { provider: q.src.name().to_string(), price: q.price, ts_ms: q.ts_ms, age_ms: now_ms - q.ts_ms, weight, verdict }
+    }
+}
+
+/// A provider that produced no quote this tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderFailure {
+    pub provider: String,
+    pub error: String, // "timeout" when it missed the deadline
+}
+
+/// Filter settings in force for the tick.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilterParams {
+    pub max_staleness_ms: u64,
+    pub min_fresh: usize,
+    pub mad_k: f64,
+    pub dispersion_bps_max: u32,
+    pub max_step_per_tick: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsensusReport {
+    pub median: f64,
+    pub mad: f64,
+    pub band: f64,
+    pub stats: ConsensusStats,
+}
+
+/// The CME side of a hybrid tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CmeReport {
+    pub cfd_price: f64,            // CFD consensus before blending
+    pub cmf_price: Option<f64>,
+    pub weight: f64,               // CMF share when used
+    pub used: bool,
+    pub fallback: Option<String>,  // why the tick fell back to CFD-only
+}
+
+/// The perp premium side of a tick (markets with a `[perp_venue]`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PremiumReport {
+    pub impact: Option<ImpactPrices>, // fetched this tick
+    pub sample: Option<f64>,          // premium sampled this tick
+    pub index: Option<f64>,           // mean premium over the window; funding's basis when set
+    pub samples: usize,               // in the window
+    pub error: Option<String>,        // why nothing was sampled this tick
+}
+
+/// This tick's funding: the basis added to the interval and what came of it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingReport {
+    pub basis: f64,
+    pub source: String,                 // "premium" or "ref-ema"
+    pub twap: f64,                      // interval average so far
+    pub predicted_rate: f64,            // for `next_settle_ms`
+    pub next_settle_ms: i64,
+    pub settlement: Option<Settlement>, // an interval closed this tick
+    pub settled_rate: Option<f64>,
+    #[serde(default)]
+    pub previous_rate: Option<f64>,     // the last settled rate, which bounds this one's change
+}
+
+/// One breaker window as evaluated this tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowReport {
+    pub secs: u32,
+    pub max_move: f64,
+    pub observed: f64, // largest move from a price within the window, as a fraction
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerEvent {
+    Tripped,
+    Reset,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakerReport {
+    #[serde(default)]
+    pub windows: Vec<WindowReport>,
+    pub tripped: bool,                  // after this tick
+    #[serde(default)]
+    pub tripped_since_ms: Option<i64>,
+    #[serde(default)]
+    pub event: Option<BreakerEvent>,    // state change on this tick
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublishedMark {
+    pub price: f64,
+    pub ts_ms: i64,
+    pub frozen: bool, // the last good mark republished unchanged
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublishRecord {
+    pub kind: String, // "index", "consensus", "price_feed", "funding", "quotes", "health"
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickReport {
+    pub symbol: String,
+    pub ts_ms: i64,
+    pub health: OracleHealth,
+    pub filters: FilterParams,
+    pub quotes: Vec<QuoteRecord>,
+    pub failures: Vec<ProviderFailure>,
+    pub consensus: Option<ConsensusReport>, // None when the tick ended before consensus
+    pub cme: Option<CmeReport>,             // None unless the market runs hybrid
+    #[serde(default)]
+    pub premium: Option<PremiumReport>,     // None without a perp venue or when funding wasn't reached
+    #[serde(default)]
+    pub funding: Option<FundingReport>,     // None when funding wasn't reached
+    pub pre_clamp_price: Option<f64>,
+    pub post_clamp_price: Option<f64>,
+    pub clamp_bounds: Option<(f64, f64)>,    // None before the first good mark
+    pub breaker: Option<BreakerReport>,      // None when the breaker wasn't reached
+    pub mark: Option<PublishedMark>,
+    pub publishes: Vec<PublishRecord>,
+    #[serde(default)]
+    pub suppressed: Vec<Suppressed>,        // updates sink publish policies held back
+    #[serde(default)]
+    pub reputation: Vec<ProviderScore>,     // after this tick's observations
+    #[serde(default)]
+    pub reputation_events: Vec<ReputationEvent>,
+}
+
+impl TickReport {
+    /// An empty report; `health` is a placeholder until the tick finishes.
+    pub fn new(symbol: &str, ts_ms: i64, filters: FilterParams) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            ts_ms,
+            health: OracleHealth {
+                symbol: symbol.to_string(),
+                status: HealthStatus::Stale,
+                reasons: Vec::new(),
+                since_ms: ts_ms,
+                last_mark_ms: None,
+                ts_ms,
+            },
+            filters,
+            quotes: Vec::new(),
+            failures: Vec::new(),
+            consensus: None,
+            cme: None,
+            premium: None,
+            funding: None,
+            pre_clamp_price: None,
+            post_clamp_price: None,
+            clamp_bounds: None,
+            breaker: None,
+            mark: None,
+            publishes: Vec::new(),
+            suppressed: Vec::new(),
+            reputation: Vec::new(),
+            reputation_events: Vec::new(),
+        }
+    }
+
+    /// Record a publish result, logging failures.
+    pub fn record_publish(&mut self, kind: &str, result: anyhow::Result<()>) {
+        let error = result.err().map(|e| {
+            tracing::warn!("publish_{kind} failed for {}: {e:?}", self.symbol);
+            format!("{e:#}")
+        });
+        self.publishes.push(PublishRecord { kind: kind.to_string(), ok: error.is_none(), error });
+    }
+
+    pub fn count(&self, verdict: QuoteVerdict) -> usize {
+        self.quotes.iter().filter(|q| q.verdict == verdict).count()
+    }
+}
+
+pub struct AuditLog {
+    path: PathBuf,
+    file: Mutex<tokio::fs::File>,
+}
+
+impl AuditLog {
+    /// Opens `path` for appending, creating it (and its directory) if needed. A
+    /// torn last line from a crash is terminated so the next report starts clean.
+    pub async fn open<P: Into<PathBuf>>(path: P) -> anyhow::Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .with_context(|| format!("opening audit log {}", path.display()))?;
+        if !ends_with_newline(&path).await? {
+            file.write_all(b"\n").await?;
+        }
+        Ok(Self { path, file: Mutex::new(file) })
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// Append one report as a single line and sync it to disk.
+    pub async fn append(&self, report: &TickReport) -> anyhow::Result<()> {
+        let mut line = serde_json::to_vec(report)?;
+        line.push(b'\n');
+        let mut file = self.file.lock().await;
+        file.write_all(&line).await?;
+        file.sync_data().await?;
+        Ok(())
+    }
+}
+
+/// Whether `path` is empty or ends in a newline, reading only its last byte.
+async fn ends_with_newline(path: &Path) -> anyhow::Result<bool> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let len = file.metadata().await?.len();
+    if len == 0 {
+        return Ok(true);
+    }
+    file.seek(SeekFrom::Start(len - 1)).await?;
+    let mut last = [0u8; 1];
+    file.read_exact(&mut last).await?;
+    Ok(last[0] == b'\n')
+}
+
+/// Filter for `query`; `None` fields match everything. Times are inclusive.
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub symbol: Option<String>,
+    pub from_ms: Option<i64>,
+    pub to_ms: Option<i64>,
+}
+
+impl AuditQuery {
+    pub fn matches(&self, r: &TickReport) -> bool {
+        self.symbol.as_deref().map_or(true, |s| s == r.symbol)
+            && self.from_ms.map_or(true, |t| r.ts_ms >= t)
+            && self.to_ms.map_or(true, |t| r.ts_ms <= t)
+    }
+}
+
+/// Reports in the log at `path` matching `q`, in the order they were written.
+/// Lines that don't parse (a torn write) are skipped with a warning.
+pub async fn query(path: &Path, q: &AuditQuery) -> anyhow::Result<Vec<TickReport>> {
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("reading audit log {}", path.display()))?;
+    let mut out = Vec::new();
+    for (i, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        match serde_json::from_str::<TickReport>(line) {
+            Ok(r) if q.matches(&r) => out.push(r),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("{}:{}: skipping unreadable audit line: {e}", path.display(), i + 1),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn appends_and_queries_by_symbol_and_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit/ticks.ndjson");
+        let log = AuditLog::open(&path).await.unwrap();
+        for (symbol, ts) in [("CORN_PERP", 1_000), ("WHEAT_PERP", 2_000), ("CORN_PERP", 3_000)] {
+            let mut r = TickReport::new(symbol, ts, FilterParams::default());
+            r.record_publish("index", Ok(()));
+            log.append(&r).await.unwrap();
+        }
+        drop(log);
+
+        // A crash mid-write leaves a torn line; reopening must not glue onto it.
+        std::fs::write(&path, [std::fs::read(&path).unwrap(), b"{\"symbol\":\"CO".to_vec()].concat()).unwrap();
+        let log = AuditLog::open(&path).await.unwrap();
+        let mut r = TickReport::new("CORN_PERP", 4_000, FilterParams::default());
+        r.record_publish("index", Err(anyhow::anyhow!("rpc down")));
+        log.append(&r).await.unwrap();
+
+        let q = AuditQuery { symbol: Some("CORN_PERP".into()), from_ms: Some(2_000), to_ms: None };
+        let got = query(&path, &q).await.unwrap();
+        assert_eq!(got.iter().map(|r| r.ts_ms).collect::<Vec<_>>(), [3_000, 4_000]);
+        assert_eq!(got[1].publishes[0].error.as_deref(), Some("rpc down"));
+        assert_eq!(query(&path, &AuditQuery::default()).await.unwrap().len(), 4);
+    }
+}
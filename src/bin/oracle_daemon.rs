@@ -1,83 +1,377 @@
 // src/bin/oracle_daemon.rs
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
+
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::watch;
 
 use autonom::{
-    config::OracleConfig,
+    audit::{self, AuditLog, AuditQuery},
+    config::{DaemonConfig, MarketConfig, PublishMode},
     oracle::Oracle,
-    providers::{
-        cfd::{NinjasCfd, OwninjaCfd},
-        CfdProvider,
+    rounds::{RoundBook, DEFAULT_CAPACITY},
+    providers::{cfd_provider_from_config, cme_provider_from_config, perp_venue_from_config, CfdProvider, ProviderOpts},
+    publishing::{
+        EvmPublisher, MultiPublisher, PolicyPublisher, Publisher, SolanaPublisher, SqlitePublisher,
+        StdoutPublisher, Web2CachePublisher,
     },
-    publishing::StdoutPublisher,
     funding::FundingEngine,
+    server::{self, MarketStore},
+    signing::{SigningPublisher, TickSigner},
+    state::StateStore,
 };
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut cfg_path = std::env::args().skip_while(|a| a != "--config").nth(1)
         .unwrap_or_else(|| "config/oracle.toml".to_string());
+    let mut http_addr = "127.0.0.1:8080".to_string();
+    let mut history_db: Option<String> = None;
+    let mut grpc_addr: Option<String> = None;
+    let mut web2_cache: Option<String> = None;
+    let mut rounds_file: Option<String> = None;
+    let mut audit_log: Option<String> = None;
+    let mut state_file: Option<String> = None;
+    let mut audit_query = AuditQuery::default();
     let mut args = std::env::args().skip(1);
     while let Some(arg) = args.next() {
         if arg == "--config" {
             if let Some(p) = args.next() {
                 cfg_path = p;
             }
+        } else if arg == "--http" {
+            if let Some(a) = args.next() {
+                http_addr = a;
+            }
+        } else if arg == "--history-db" {
+            history_db = args.next();
+        } else if arg == "--grpc" {
+            grpc_addr = args.next();
+        } else if arg == "--web2-cache" {
+            web2_cache = args.next();
+        } else if arg == "--rounds-file" {
+            rounds_file = args.next();
+        } else if arg == "--audit-log" {
+            audit_log = args.next();
+        } else if arg == "--state-file" {
+            state_file = args.next();
+        } else if arg == "--symbol" {
+            audit_query.symbol = args.next();
+        } else if arg == "--from" {
+            audit_query.from_ms = args.next().map(|v| v.parse()).transpose()?;
+        } else if arg == "--to" {
+            audit_query.to_ms = args.next().map(|v| v.parse()).transpose()?;
+        }
+    }
+    // --- `oracle_daemon check-config [--config <path>]`: validate, print the effective config, exit
+    if std::env::args().nth(1).as_deref() == Some("check-config") {
+        std::process::exit(check_config(&cfg_path));
+    }
+    // --- `oracle_daemon audit --audit-log <path> [--symbol S] [--from ms] [--to ms]`: print matching reports
+    if std::env::args().nth(1).as_deref() == Some("audit") {
+        let path = audit_log.ok_or("audit needs --audit-log <path>")?;
+        for report in audit::query(path.as_ref(), &audit_query).await? {
+            println!("{}", serde_json::to_string(&report)?);
         }
+        return Ok(());
     }
 
-    // --- load OracleConfig from TOML; fall back to Default if missing/unparseable
-    let cfg = match std::fs::read_to_string(&cfg_path) {
-        Ok(s) => match toml::from_str::<OracleConfig>(&s) {
-            Ok(v) => v,
-            Err(e) => {
-                eprintln!("CONFIG DESERIALIZE ERROR [{}]:\n{}", cfg_path, e);
-                OracleConfig::default()
-            }
-        },
+    let http_addr: SocketAddr = http_addr.parse()?;
+    let grpc_addr: Option<SocketAddr> = grpc_addr.map(|a| a.parse()).transpose()?;
+
+    // --- load + validate the daemon config; refuse to start on any error
+    let cfg = match DaemonConfig::load(&cfg_path) {
+        Ok(cfg) => cfg,
         Err(e) => {
-            eprintln!("CONFIG READ ERROR [{}]:\n{}", cfg_path, e);
-            OracleConfig::default()
+            eprintln!("CONFIG ERROR [{}]: {:#}", cfg_path, e);
+            std::process::exit(2);
         }
     };
 
-    // --- publisher (no Arc; Publisher is implemented for the concrete type)
-    let publisher = StdoutPublisher {};
+    // --- publishers: in-memory store backing the HTTP/gRPC read APIs + the [publish] sink
+    let store = MarketStore::new();
+    // Every sink but the HTTP/gRPC store can have a [publish.policy.<sink>] deviation/heartbeat gate.
+    let gated = |sink: &str, inner: Arc<dyn Publisher>| -> Arc<dyn Publisher> {
+        match cfg.publish.policy.get(sink) {
+            Some(policy) => Arc::new(PolicyPublisher::new(sink, *policy, inner)),
+            None => inner,
+        }
+    };
+    let mut sinks = MultiPublisher::new().with(Arc::new(store.clone()));
+    if cfg.publish.mode == PublishMode::Stdout {
+        sinks.push(gated("stdout", Arc::new(StdoutPublisher {})));
+    }
 
-    let ninjas = std::sync::Arc::new(NinjasCfd::from_env()?);
-    let owninja = std::sync::Arc::new(OwninjaCfd); // keep your mock if you want diversity
+    // --- Chainlink-style rounds; persisted across restarts with --rounds-file <path>
+    let rounds = Arc::new(match &rounds_file {
+        Some(path) => RoundBook::open(path, DEFAULT_CAPACITY).await?,
+        None => RoundBook::in_memory(DEFAULT_CAPACITY),
+    });
+    sinks.push(gated("rounds", rounds.clone()));
 
-    // --- CFD providers (add/remove as your project implements them)
-    let cfd_providers: Vec<std::sync::Arc<dyn CfdProvider + Send + Sync>> =
-        vec![ninjas, owninja];
+    // --- optional SQLite history (e.g. --history-db sqlite://var/history.db)
+    if let Some(url) = &history_db {
+        sinks.push(gated("sqlite", Arc::new(SqlitePublisher::connect(url).await?)));
+    }
+    // --- web2 cache snapshot: [publish] web2_cache_path, or --web2-cache <path> to override
+    let web2_cache = web2_cache.map(Into::into).or(match cfg.publish.mode {
+        PublishMode::Web2Cache => cfg.publish.web2_cache_path.clone(),
+        PublishMode::Stdout => None,
+    });
+    if let Some(path) = web2_cache {
+        sinks.push(gated("web2_cache", Arc::new(Web2CachePublisher::new(path).await?)));
+    }
+    // --- optional on-chain push when EVM_RPC_URL (+ EVM_FEED_CONTRACT, EVM_PRIVATE_KEY) is set
+    if std::env::var("EVM_RPC_URL").is_ok() {
+        let evm = EvmPublisher::from_env()?;
+        eprintln!("pushing marks on-chain from {}", evm.sender());
+        sinks.push(gated("evm", Arc::new(evm)));
+    }
+    // --- optional Solana price accounts when SOLANA_RPC_URL (+ SOLANA_PROGRAM_ID, SOLANA_KEYPAIR) is set
+    if std::env::var("SOLANA_RPC_URL").is_ok() {
+        let sol = SolanaPublisher::from_env()?;
+        eprintln!("writing Solana price accounts as {}", sol.authority());
+        sinks.push(gated("solana", Arc::new(sol)));
+    }
+    let mut publisher: Arc<dyn Publisher> = Arc::new(sinks);
 
-    // --- funding engine (simple default; adjust if you expose config knobs)
-    let funding_engine = FundingEngine::new(
-        0.02,      // kappa: strength of mean-reversion toward the reference
-        0.005,     // cap: max funding magnitude per interval (e.g., 0.5%)
-        8 * 60 * 60, // interval_sec: typical 8h funding window
-    );
+    // --- sign every mark when ORACLE_SIGNING_KEY is set
+    if std::env::var("ORACLE_SIGNING_KEY").is_ok() {
+        let signer = TickSigner::from_env()?;
+        eprintln!("signing marks as {}", signer.signer_hex());
+        publisher = Arc::new(SigningPublisher::new(signer, publisher));
+    }
 
-    // NOTE: this matches your current Oracle::new signature:
-    // Oracle::new(cfg, publisher, cfds, funding_engine)
-    let mut oracle = Oracle::new(cfg, publisher, cfd_providers, funding_engine);
+    // --- append-only NDJSON record of every tick (--audit-log <path>)
+    let audit_log = match &audit_log {
+        Some(path) => Some(Arc::new(AuditLog::open(path).await?)),
+        None => None,
+    };
 
-    // drive ticks at cfg.poll_ms (fallback 1000ms if unset/zero)
-    let tick_ms = if oracle.cfg.poll_ms == 0 { 1000 } else { oracle.cfg.poll_ms };
-    let mut ticker = tokio::time::interval(Duration::from_millis(tick_ms as u64));
+    // --- runtime state across restarts: [state] path, or --state-file <path> to override
+    let state_store = match state_file.map(std::path::PathBuf::from).or_else(|| cfg.state.path.clone()) {
+        Some(path) => Some(Arc::new(StateStore::open(path).await?)),
+        None => None,
+    };
+
+    let mut servers = Vec::new();
+    let http_state = server::http::AppState::new(store.clone()).with_rounds(rounds);
+    servers.push(tokio::spawn(async move {
+        if let Err(e) = server::http::serve(http_addr, http_state).await {
+            eprintln!("HTTP SERVER ERROR [{}]: {}", http_addr, e);
+        }
+    }));
+    if let Some(addr) = grpc_addr {
+        let grpc_store = store.clone();
+        servers.push(tokio::spawn(async move {
+            if let Err(e) = server::grpc::serve(addr, grpc_store).await {
+                eprintln!("GRPC SERVER ERROR [{}]: {}", addr, e);
+            }
+        }));
+    }
+
+    // --- CFD providers: every enabled [[cfd_providers]] entry, with its timeout and weight
+    let mut cfd_providers: Vec<Arc<dyn CfdProvider + Send + Sync>> = Vec::new();
+    let mut provider_opts = HashMap::new();
+    for p in cfg.enabled_providers() {
+        let provider = cfd_provider_from_config(p)?;
+        provider_opts.insert(provider.name().to_string(), ProviderOpts::from(p));
+        cfd_providers.push(provider);
+    }
+    // --- optional [cme] futures source: markets without `mode_cfd_only` blend its CMF index in
+    let cme = match &cfg.cme {
+        Some(c) => {
+            let cme = cme_provider_from_config(c)?;
+            provider_opts.insert(cme.name().to_string(), ProviderOpts::from(c));
+            Some(cme)
+        }
+        None => None,
+    };
+    // --- optional [perp_venue]: funding is based on its premium index instead of the mark EMA
+    let venue = match &cfg.perp_venue {
+        Some(c) => {
+            let venue = perp_venue_from_config(c)?;
+            provider_opts.insert(venue.name().to_string(), ProviderOpts::from(c));
+            Some((venue, c.clone()))
+        }
+        None => None,
+    };
+
+    // --- one oracle pipeline per market; providers and publishers are shared.
+    // Reloaded configs arrive on `reload_rx` and are applied between ticks; on
+    // shutdown each market saves its state before exiting.
+    let (reload_tx, reload_rx) = watch::channel(Arc::new(cfg.markets()?));
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    let mut markets = Vec::new();
+    for m in cfg.markets()? {
+        let funding_engine = FundingEngine::from_config(&m.funding);
+        let mut tick_ms = m.oracle.tick_interval_ms;
+        let mut oracle = Oracle::new(m.oracle, publisher.clone(), cfd_providers.clone(), funding_engine)
+            .with_provider_opts(provider_opts.clone())
+            .with_reputation(cfg.reputation.clone());
+        if let Some(cme) = &cme {
+            oracle = oracle.with_cme(cme.clone());
+        }
+        if let Some((venue, c)) = &venue {
+            oracle = oracle.with_venue(venue.clone(), c.clone());
+        }
+        if let Some(store) = &state_store {
+            match store.get(&oracle.cfg.symbol).await {
+                Some(saved) => {
+                    let parts = oracle.restore(saved, &cfg.state);
+                    let parts = if parts.is_empty() { "nothing (too old)".to_string() } else { parts.join(", ") };
+                    eprintln!("restored {} from {}: {parts}", oracle.cfg.symbol, store.path().display());
+                }
+                None => eprintln!("no saved state for {} in {}", oracle.cfg.symbol, store.path().display()),
+            }
+        }
+        let mode = if cme.is_some() && !oracle.cfg.mode_cfd_only { "hybrid CMF+CFD" } else { "CFD-only" };
+        let basis = if venue.is_some() { "venue premium" } else { "mark EMA" };
+        eprintln!("marking {} every {}ms ({mode}, funding on {basis})", oracle.cfg.symbol, tick_ms);
+        let mut reload_rx = reload_rx.clone();
+        let mut shutdown_rx = shutdown_rx.clone();
+        let audit_log = audit_log.clone();
+        let state_store = state_store.clone();
+        let save_every = Duration::from_millis(cfg.state.save_interval_ms);
+        markets.push(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_millis(tick_ms));
+            let mut save_tick = tokio::time::interval_at(tokio::time::Instant::now() + save_every, save_every);
+            loop {
+                tokio::select! {
+                    _ = shutdown_rx.changed() => break,
+                    _ = save_tick.tick(), if state_store.is_some() => {
+                        save_state(state_store.as_deref(), &oracle.cfg.symbol, oracle.snapshot()).await;
+                    }
+                    _ = ticker.tick() => {
+                        let report = oracle.tick_once().await;
+                        if let Some(log) = &audit_log {
+                            if let Err(e) = log.append(&report).await {
+                                eprintln!("AUDIT LOG ERROR [{}]: {:#}", log.path().display(), e);
+                            }
+                        }
+                    }
+                    Ok(()) = reload_rx.changed() => {
+                        let all = reload_rx.borrow_and_update().clone();
+                        let Some(m) = all.iter().find(|m| m.oracle.symbol == oracle.cfg.symbol) else { continue };
+                        if let Err(e) = oracle.apply_config(m.oracle.clone()) {
+                            eprintln!("RELOAD REJECTED [{}]: {:#}", oracle.cfg.symbol, e);
+                            continue;
+                        }
+                        oracle.apply_funding(&m.funding);
+                        if oracle.cfg.tick_interval_ms != tick_ms {
+                            tick_ms = oracle.cfg.tick_interval_ms;
+                            ticker = tokio::time::interval(Duration::from_millis(tick_ms));
+                        }
+                    }
+                }
+            }
+            save_state(state_store.as_deref(), &oracle.cfg.symbol, oracle.snapshot()).await;
+        }));
+    }
 
-    // simple loop with Ctrl-C shutdown
+    // --- hot reload on SIGHUP or when the config file's mtime changes
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut watch_tick = tokio::time::interval(Duration::from_secs(2));
+    let mut last_mtime = mtime(&cfg_path);
+    let mut current = cfg;
     loop {
         tokio::select! {
-            _ = ticker.tick() => {
-                oracle.tick_once().await;
-            }
             _ = tokio::signal::ctrl_c() => {
                 eprintln!("received Ctrl-C, exiting");
                 break;
             }
+            _ = terminate.recv() => {
+                eprintln!("received SIGTERM, exiting");
+                break;
+            }
+            _ = hangup.recv() => {
+                last_mtime = mtime(&cfg_path);
+                reload(&cfg_path, &mut current, &reload_tx);
+            }
+            _ = watch_tick.tick() => {
+                let m = mtime(&cfg_path);
+                if m != last_mtime {
+                    last_mtime = m;
+                    reload(&cfg_path, &mut current, &reload_tx);
+                }
+            }
         }
     }
 
+    shutdown_tx.send_replace(true);
+    for m in markets {
+        let _ = m.await;
+    }
+    for s in servers {
+        s.abort();
+    }
     Ok(())
 }
+
+/// Exit code 0 when the config is valid (effective values go to stdout), 1 otherwise.
+fn check_config(path: &str) -> i32 {
+    let parsed = std::fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|s| toml::from_str::<DaemonConfig>(&s).map_err(anyhow::Error::from));
+    let cfg = match parsed {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            eprintln!("{}: {:#}", path, e);
+            return 1;
+        }
+    };
+    let issues = cfg.issues();
+    if !issues.is_empty() {
+        for issue in &issues {
+            eprintln!("{}: {}", path, issue);
+        }
+        eprintln!("{} problem(s) found", issues.len());
+        return 1;
+    }
+    match cfg.explain() {
+        Ok(out) => {
+            print!("{out}");
+            eprintln!("{}: ok", path);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}: {:#}", path, e);
+            1
+        }
+    }
+}
+
+async fn save_state(store: Option<&StateStore>, symbol: &str, snapshot: autonom::state::OracleState) {
+    let Some(store) = store else { return };
+    if let Err(e) = store.save(symbol, snapshot).await {
+        eprintln!("STATE SAVE ERROR [{}]: {:#}", store.path().display(), e);
+    }
+}
+
+fn mtime(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Load, validate and diff the config at `path`; on success hand the new market
+/// settings to the running oracles. Any error keeps the current config, and
+/// restart-only sections stay as they were (see `DaemonConfig::applied`).
+fn reload(path: &str, current: &mut DaemonConfig, tx: &watch::Sender<Arc<Vec<MarketConfig>>>) {
+    let result = DaemonConfig::load(path).and_then(|new| {
+        let diff = current.reload_diff(&new)?;
+        Ok((new.markets()?, diff, new))
+    });
+    match result {
+        Ok((_, diff, _)) if diff.is_empty() => eprintln!("config reload [{}]: no changes", path),
+        Ok((markets, diff, new)) => {
+            for line in &diff {
+                eprintln!("config reload [{}]: {}", path, line);
+            }
+            tx.send_replace(Arc::new(markets));
+            *current = current.applied(&new);
+        }
+        Err(e) => eprintln!("RELOAD REJECTED [{}]: {:#}; keeping current config", path, e),
+    }
+}
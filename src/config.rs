@@ -1,23 +1,55 @@
 // src/config.rs
-use serde::Deserialize;
+use anyhow::{anyhow, bail, Context};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use crate::providers::{CFD_PROVIDER_NAMES, CME_PROVIDER_NAMES, PERP_VENUE_NAMES};
+
+/// The whole daemon config file: `[oracle]`, `[[markets]]`, `[[cfd_providers]]`,
+/// `[cme]`, `[perp_venue]`, `[publish]`, `[funding]`, `[reputation]`, `[state]` and `[metrics]`
+/// (see `config/oracle.example.toml`).
+///
+/// Without `[[markets]]` the daemon runs the single market described by
+/// `[oracle]`. With them, `[oracle]` and `[funding]` hold the defaults and each
+/// `[[markets]]` table overrides any of their keys (funding keys go in a nested
+/// `funding` table).
 #[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DaemonConfig {
+    pub oracle: OracleConfig,
+    #[serde(default)]                            pub markets: Vec<toml::value::Table>,
+    #[serde(default)]                            pub cfd_providers: Vec<CfdProviderConfig>,
+    #[serde(default)]                            pub cme: Option<CmeProviderConfig>,
+    #[serde(default)]                            pub perp_venue: Option<PerpVenueConfig>,
+    #[serde(default)]                            pub publish: PublishConfig,
+    #[serde(default)]                            pub funding: FundingConfig,
+    #[serde(default)]                            pub reputation: ReputationConfig,
+    #[serde(default)]                            pub state: StateConfig,
+    #[serde(default)]                            pub metrics: MetricsConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct OracleConfig {
-    pub symbol: String,
-    pub expo: i8,
-    #[serde(default = "d_poll_ms")]              pub poll_ms: u64,
+    #[serde(default)]                            pub symbol: String,
+    #[serde(default = "d_expo")]                 pub expo: i8,
+    #[serde(default = "d_tick_interval_ms", alias = "poll_ms")] pub tick_interval_ms: u64,
+    #[serde(default = "d_cb_per_min")]           pub circuit_breaker_per_min: f64,   // max move within 1 minute
+    #[serde(default)]                            pub circuit_breaker_windows: Vec<BreakerWindow>, // further windows, e.g. 5m/15m
+    #[serde(default = "d_cb_cooldown_ms")]       pub circuit_breaker_cooldown_ms: u64, // minimum halt after a trip
+    #[serde(default = "d_cb_settle_ms")]         pub circuit_breaker_settle_ms: u64,   // steady this long before a reset
+    #[serde(default)]                            pub tick_deadline_ms: Option<u64>, // quote collection budget; default tick_interval_ms
     #[serde(default)]                            pub cfd_twap_sec: u32,
     #[serde(default)]                            pub cfd_median_sec: u32,
-    #[serde(default = "d_stale_ms")]             pub cfd_max_staleness_ms: u64,
     #[serde(default = "d_jump_pct")]             pub cfd_jump_pct: f64,
     #[serde(default = "d_cmf_days")]             pub cmf_target_days: f64,
     #[serde(default = "d_roll_hike")]            pub roll_hike_im_pct: f64,
-    #[serde(default = "d_funding_kappa")]        pub funding_kappa: f64,
-    #[serde(default = "d_funding_cap")]          pub funding_cap: f64,
-    #[serde(default = "d_funding_interval")]     pub funding_interval_sec: u32,
     #[serde(default)]                            pub trading_hours_only: bool,
-    #[serde(default)]                            pub mode_cfd_only: bool,
+    #[serde(default)]                            pub mode_cfd_only: bool,   // false: blend in the CMF index when `[cme]` is set
+    #[serde(default = "d_cme_weight")]           pub cme_weight: f64,       // CMF share of a hybrid mark, 0..=1
+    #[serde(default = "d_cme_stale_ms")]         pub cme_max_staleness_ms: u64, // older legs: fall back to CFD-only
     #[serde(default = "d_min_fresh")]            pub cfd_min_fresh: usize,
     #[serde(default = "d_tau_ms")]               pub cfd_tau_ms: u64,
     #[serde(default = "d_mad_k")]                pub cfd_mad_k: f64,
@@ -25,14 +57,16 @@ pub struct OracleConfig {
     #[serde(default = "d_hours_guard")]          pub hours_guard: String,
     #[serde(default = "d_max_step")]             pub max_step_per_tick: f64,
 }
-fn d_poll_ms() -> u64 { 2000 }
-fn d_stale_ms() -> u64 { 90_000 }
+fn d_expo() -> i8 { -8 }
+fn d_tick_interval_ms() -> u64 { 2000 }
+fn d_cb_per_min() -> f64 { 0.07 }
+fn d_cb_cooldown_ms() -> u64 { 60_000 }
+fn d_cb_settle_ms() -> u64 { 30_000 }
 fn d_jump_pct() -> f64 { 0.05 }
 fn d_cmf_days() -> f64 { 30.0 }
+fn d_cme_weight() -> f64 { 0.5 }
+fn d_cme_stale_ms() -> u64 { 120_000 }
 fn d_roll_hike() -> f64 { 0.25 }
-fn d_funding_kappa() -> f64 { 0.5 }
-fn d_funding_cap() -> f64 { 0.004 }
-fn d_funding_interval() -> u32 { 8*3600 }
 fn d_min_fresh() -> usize { 2 }
 fn d_tau_ms() -> u64 { 8000 }
 fn d_mad_k() -> f64 { 3.5 }
@@ -44,29 +78,731 @@ pub fn ms(d: u64) -> std::time::Duration { Duration::from_millis(d) }
 
 impl Default for OracleConfig {
     fn default() -> Self {
-        let c = Self {
+        Self {
             symbol: "".to_string(),
-            expo: 0,
-            poll_ms: 0,
+            expo: d_expo(),
+            tick_interval_ms: d_tick_interval_ms(),
+            circuit_breaker_per_min: d_cb_per_min(),
+            circuit_breaker_windows: Vec::new(),
+            circuit_breaker_cooldown_ms: d_cb_cooldown_ms(),
+            circuit_breaker_settle_ms: d_cb_settle_ms(),
+            tick_deadline_ms: None,
             cfd_twap_sec: 0,
             cfd_median_sec: 0,
-            cfd_max_staleness_ms: 0,
             cfd_jump_pct: 0.0,
             cmf_target_days: 0.0,
             roll_hike_im_pct: 0.0,
-            funding_kappa: 0.0,
-            funding_cap: 0.0,
-            funding_interval_sec: 0,
             trading_hours_only: false,
             mode_cfd_only: false,
+            cme_weight: d_cme_weight(),
+            cme_max_staleness_ms: d_cme_stale_ms(),
             cfd_min_fresh: 2,
             cfd_tau_ms: 20_000,
             cfd_mad_k: 6.0,
             cfd_dispersion_bps_max: 80,
             hours_guard: "cme".into(),
             max_step_per_tick: 0.02,
+        }
+    }
+}
+
+/// A circuit-breaker window: trip when the mark moves more than `max_move` (a
+/// fraction) from any price seen within the last `secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BreakerWindow {
+    pub secs: u32,
+    pub max_move: f64,
+}
+
+/// One `[[cfd_providers]]` entry; `name` picks the `CfdProvider` implementation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CfdProviderConfig {
+    pub name: String,
+    #[serde(default = "d_provider_timeout_ms")]  pub timeout_ms: u64,
+    #[serde(default = "d_provider_weight")]      pub weight: f64,
+    #[serde(default = "d_true")]                 pub enabled: bool,
+}
+fn d_provider_timeout_ms() -> u64 { 850 }
+fn d_provider_weight() -> f64 { 1.0 }
+fn d_true() -> bool { true }
+
+/// `[cme]`: the futures source for hybrid marks; `name` picks the `CmeProvider`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CmeProviderConfig {
+    pub name: String,
+    #[serde(default = "d_provider_timeout_ms")]  pub timeout_ms: u64,
+}
+
+/// `[perp_venue]`: where our perp trades; `name` picks the `PerpVenue`. Its
+/// impact prices against the mark give the premium index funding is based on
+/// (see `premium`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PerpVenueConfig {
+    pub name: String,
+    #[serde(default = "d_provider_timeout_ms")]  pub timeout_ms: u64,
+    #[serde(default = "d_impact_notional")]      pub impact_notional: f64,  // quote currency walked into each side of the book
+    #[serde(default = "d_premium_sample_ms")]    pub sample_ms: u64,        // at most one premium sample per this
+    #[serde(default = "d_premium_window_ms")]    pub window_ms: u64,        // premium index = mean of the samples within this
+    #[serde(default = "d_premium_stale_ms")]     pub max_staleness_ms: u64, // older books aren't sampled
+}
+fn d_impact_notional() -> f64 { 10_000.0 }
+fn d_premium_sample_ms() -> u64 { 1_000 }
+fn d_premium_window_ms() -> u64 { 60_000 }
+fn d_premium_stale_ms() -> u64 { 5_000 }
+
+impl PerpVenueConfig {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            timeout_ms: d_provider_timeout_ms(),
+            impact_notional: d_impact_notional(),
+            sample_ms: d_premium_sample_ms(),
+            window_ms: d_premium_window_ms(),
+            max_staleness_ms: d_premium_stale_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishMode {
+    #[default]
+    Stdout,
+    Web2Cache,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PublishConfig {
+    #[serde(default)]                            pub mode: PublishMode,
+    #[serde(default)]                            pub web2_cache_path: Option<PathBuf>,
+    #[serde(default)]                            pub policy: BTreeMap<String, PublishPolicy>, // by sink, see `PUBLISH_SINKS`
+}
+
+/// `[publish.policy.<sink>]`: when a sink gets a new mark or funding update (see
+/// `publishing::policy`). A sink without one gets every update.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PublishPolicy {
+    #[serde(default)]                            pub deviation_bps: Option<f64>,         // mark move since the last published one
+    #[serde(default)]                            pub funding_deviation_bps: Option<f64>, // funding rate change since the last published one
+    #[serde(default)]                            pub heartbeat_ms: Option<u64>,          // publish at least this often regardless
+}
+
+/// Sinks a `[publish.policy.<sink>]` table can name.
+pub const PUBLISH_SINKS: &[&str] = &["stdout", "rounds", "sqlite", "web2_cache", "evm", "solana"];
+
+/// `[funding]` (see `funding::FundingEngine`). The interest baseline is
+/// annualized and the caps are per hour; `FundingEngine::from_config` scales
+/// them all to `interval_sec`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FundingConfig {
+    #[serde(default)]                            pub target_annualized_bps: f64,      // interest baseline
+    #[serde(default = "d_clamp_bps_per_hour")]   pub clamp_bps_per_hour: f64,         // max |rate|
+    #[serde(default)]                            pub premium_clamp_bps_per_hour: f64, // premium within this of the baseline pays the baseline
+    #[serde(default)]                            pub max_change_bps_per_hour: Option<f64>, // max move from the previous settled rate
+    #[serde(default = "d_ema_alpha")]            pub ema_alpha: f64,
+    #[serde(default = "d_kappa")]                pub kappa: f64,
+    #[serde(default = "d_funding_interval")]     pub interval_sec: u32,
+}
+fn d_clamp_bps_per_hour() -> f64 { 8.0 }
+fn d_ema_alpha() -> f64 { 0.005 }
+fn d_kappa() -> f64 { 0.02 }
+fn d_funding_interval() -> u32 { 8*3600 }
+
+impl Default for FundingConfig {
+    fn default() -> Self {
+        Self {
+            target_annualized_bps: 0.0,
+            clamp_bps_per_hour: d_clamp_bps_per_hour(),
+            premium_clamp_bps_per_hour: 0.0,
+            max_change_bps_per_hour: None,
+            ema_alpha: d_ema_alpha(),
+            kappa: d_kappa(),
+            interval_sec: d_funding_interval(),
+        }
+    }
+}
+
+impl FundingConfig {
+    /// Max |rate| per funding interval, as a fraction.
+    pub fn cap_per_interval(&self) -> f64 {
+        self.per_interval(self.clamp_bps_per_hour)
+    }
+
+    /// The interest baseline per funding interval, as a fraction.
+    pub fn interest_per_interval(&self) -> f64 {
+        self.target_annualized_bps / 10_000.0 * self.interval_sec as f64 / (365.0 * 86_400.0)
+    }
+
+    /// How far the premium term may sit from the baseline per interval, as a fraction.
+    pub fn premium_clamp_per_interval(&self) -> f64 {
+        self.per_interval(self.premium_clamp_bps_per_hour)
+    }
+
+    /// Max change between settled rates, as a fraction; `None` for no limit.
+    pub fn max_change_per_interval(&self) -> Option<f64> {
+        self.max_change_bps_per_hour.map(|bps| self.per_interval(bps))
+    }
+
+    fn per_interval(&self, bps_per_hour: f64) -> f64 {
+        bps_per_hour / 10_000.0 * self.interval_sec as f64 / 3600.0
+    }
+}
+
+/// `[reputation]`: provider scoring and automatic quarantine (see `reputation`).
+/// Shared by every market; each market scores the providers separately.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReputationConfig {
+    #[serde(default = "d_true")]                 pub enabled: bool,       // false: score only, never quarantine
+    #[serde(default = "d_rep_window")]           pub window: u32,         // EWMA span, in observations
+    #[serde(default = "d_rep_dev_scale")]        pub deviation_bps_scale: f64, // mean deviation that zeroes the score
+    #[serde(default = "d_rep_quarantine")]       pub quarantine_below: f64,
+    #[serde(default = "d_rep_reinstate")]        pub reinstate_above: f64,
+    #[serde(default = "d_rep_probation_ms")]     pub probation_ms: u64,   // minimum time in quarantine
+    #[serde(default = "d_rep_min_samples")]      pub min_samples: u32,    // observations before a provider can be quarantined
+}
+fn d_rep_window() -> u32 { 50 }
+fn d_rep_dev_scale() -> f64 { 100.0 }
+fn d_rep_quarantine() -> f64 { 0.5 }
+fn d_rep_reinstate() -> f64 { 0.7 }
+fn d_rep_probation_ms() -> u64 { 600_000 }
+fn d_rep_min_samples() -> u32 { 20 }
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: d_rep_window(),
+            deviation_bps_scale: d_rep_dev_scale(),
+            quarantine_below: d_rep_quarantine(),
+            reinstate_above: d_rep_reinstate(),
+            probation_ms: d_rep_probation_ms(),
+            min_samples: d_rep_min_samples(),
+        }
+    }
+}
+
+/// `[state]`: where runtime state is saved between restarts (see `state`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateConfig {
+    #[serde(default)]                            pub path: Option<PathBuf>,    // None: nothing is saved
+    #[serde(default = "d_state_save_ms")]        pub save_interval_ms: u64,
+    #[serde(default = "d_state_max_age_ms")]     pub max_age_ms: u64,          // older price state is dropped
+    #[serde(default = "d_state_rep_age_ms")]     pub reputation_max_age_ms: u64, // older provider scores are dropped
+}
+fn d_state_save_ms() -> u64 { 10_000 }
+fn d_state_max_age_ms() -> u64 { 900_000 }
+fn d_state_rep_age_ms() -> u64 { 86_400_000 }
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            save_interval_ms: d_state_save_ms(),
+            max_age_ms: d_state_max_age_ms(),
+            reputation_max_age_ms: d_state_rep_age_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsConfig {
+    #[serde(default)]                            pub enabled: bool,
+    #[serde(default = "d_namespace")]            pub namespace: String,
+}
+fn d_namespace() -> String { "oracle".into() }
+
+impl Default for MetricsConfig {
+    fn default() -> Self { Self { enabled: false, namespace: d_namespace() } }
+}
+
+impl DaemonConfig {
+    /// Read, parse and validate a config file.
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::from_toml_str(&s).with_context(|| format!("loading {}", path.display()))
+    }
+
+    pub fn from_toml_str(s: &str) -> anyhow::Result<Self> {
+        let cfg: Self = toml::from_str(s)?;
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    /// The markets to run, with `[oracle]`/`[funding]` defaults applied.
+    pub fn markets(&self) -> anyhow::Result<Vec<MarketConfig>> {
+        if self.markets.is_empty() {
+            return Ok(vec![MarketConfig { oracle: self.oracle.clone(), funding: self.funding.clone() }]);
+        }
+        (0..self.markets.len()).map(|i| self.market_at(i)).collect()
+    }
+
+    /// `[[markets]]` entry `i` resolved over the defaults. Errors carry the
+    /// entry's path as their context.
+    fn market_at(&self, i: usize) -> anyhow::Result<MarketConfig> {
+        let mut over = self.markets[i].clone();
+        let funding_over = match over.remove("funding") {
+            Some(toml::Value::Table(t)) => t,
+            Some(_) => return Err(anyhow!("must be a table")).context(format!("markets[{i}].funding")),
+            None => toml::value::Table::new(),
         };
-        c
+        Ok(MarketConfig {
+            oracle: overlay(&toml::Value::try_from(&self.oracle)?, over)
+                .try_into()
+                .with_context(|| format!("markets[{i}]"))?,
+            funding: overlay(&toml::Value::try_from(&self.funding)?, funding_over)
+                .try_into()
+                .with_context(|| format!("markets[{i}].funding"))?,
+        })
+    }
+
+    /// Fails with every problem found, one `path: message` per line.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let issues = self.issues();
+        if issues.is_empty() {
+            return Ok(());
+        }
+        bail!("{}", issues.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))
+    }
+
+    /// Range and cross-field checks over the resolved config.
+    pub fn issues(&self) -> Vec<ConfigIssue> {
+        let mut out = Issues::default();
+        let enabled = self.cfd_providers.iter().filter(|p| p.enabled).count();
+
+        let markets: Vec<(String, MarketConfig)> = if self.markets.is_empty() {
+            vec![("oracle".into(), MarketConfig { oracle: self.oracle.clone(), funding: self.funding.clone() })]
+        } else {
+            (0..self.markets.len())
+                .filter_map(|i| match self.market_at(i) {
+                    Ok(m) => Some((format!("markets[{i}]"), m)),
+                    Err(e) => {
+                        out.push(e.to_string(), e.root_cause().to_string());
+                        None
+                    }
+                })
+                .collect()
+        };
+        let mut symbols = std::collections::HashSet::new();
+        for (at, m) in &markets {
+            let o = &m.oracle;
+            out.check(!o.symbol.trim().is_empty(), format!("{at}.symbol"), "must not be empty");
+            out.check(symbols.insert(o.symbol.as_str()), format!("{at}.symbol"), format!("`{}` is listed twice", o.symbol));
+            out.check(matches!(o.expo, -8 | -10), format!("{at}.expo"), format!("{} is not supported (use -8 or -10)", o.expo));
+            out.check(o.tick_interval_ms > 0, format!("{at}.tick_interval_ms"), "must be > 0");
+            if let Some(d) = o.tick_deadline_ms {
+                out.check(
+                    d > 0 && d <= o.tick_interval_ms,
+                    format!("{at}.tick_deadline_ms"),
+                    format!("must be in 1..={} (tick_interval_ms)", o.tick_interval_ms),
+                );
+            }
+            out.check(o.cfd_tau_ms > 0, format!("{at}.cfd_tau_ms"), "must be > 0");
+            out.check(o.cfd_mad_k.is_finite() && o.cfd_mad_k > 0.0, format!("{at}.cfd_mad_k"), "must be > 0");
+            out.check(o.cfd_min_fresh >= 1, format!("{at}.cfd_min_fresh"), "must be >= 1");
+            out.check(
+                o.cfd_min_fresh <= enabled.max(1),
+                format!("{at}.cfd_min_fresh"),
+                format!("{} fresh quotes can never be met with {enabled} enabled provider(s)", o.cfd_min_fresh),
+            );
+            out.check(
+                o.max_step_per_tick.is_finite() && o.max_step_per_tick > 0.0 && o.max_step_per_tick < 1.0,
+                format!("{at}.max_step_per_tick"),
+                "must be in (0, 1)",
+            );
+            out.check(
+                o.circuit_breaker_per_min.is_finite() && o.circuit_breaker_per_min > 0.0,
+                format!("{at}.circuit_breaker_per_min"),
+                "must be > 0",
+            );
+            for (j, w) in o.circuit_breaker_windows.iter().enumerate() {
+                out.check(w.secs > 0, format!("{at}.circuit_breaker_windows[{j}].secs"), "must be > 0");
+                out.check(
+                    w.max_move.is_finite() && w.max_move > 0.0,
+                    format!("{at}.circuit_breaker_windows[{j}].max_move"),
+                    "must be > 0",
+                );
+            }
+            out.check(
+                HOURS_GUARDS.contains(&o.hours_guard.as_str()),
+                format!("{at}.hours_guard"),
+                format!("unknown value `{}` (expected one of: {})", o.hours_guard, HOURS_GUARDS.join(", ")),
+            );
+            out.check((0.0..=1.0).contains(&o.cme_weight), format!("{at}.cme_weight"), "must be in [0, 1]");
+            out.check(o.cme_max_staleness_ms > 0, format!("{at}.cme_max_staleness_ms"), "must be > 0");
+            out.check(o.cmf_target_days.is_finite() && o.cmf_target_days >= 0.0, format!("{at}.cmf_target_days"), "must be >= 0");
+
+            let at = if self.markets.is_empty() { "funding".to_string() } else { format!("{at}.funding") };
+            let f = &m.funding;
+            out.check(f.ema_alpha > 0.0 && f.ema_alpha <= 1.0, format!("{at}.ema_alpha"), "must be in (0, 1]");
+            out.check(f.clamp_bps_per_hour.is_finite() && f.clamp_bps_per_hour >= 0.0, format!("{at}.clamp_bps_per_hour"), "must be >= 0");
+            out.check(
+                f.premium_clamp_bps_per_hour.is_finite() && f.premium_clamp_bps_per_hour >= 0.0,
+                format!("{at}.premium_clamp_bps_per_hour"),
+                "must be >= 0",
+            );
+            out.check(
+                f.max_change_bps_per_hour.map_or(true, |bps| bps.is_finite() && bps > 0.0),
+                format!("{at}.max_change_bps_per_hour"),
+                "must be > 0",
+            );
+            out.check(f.kappa.is_finite() && f.kappa >= 0.0, format!("{at}.kappa"), "must be >= 0");
+            out.check(f.target_annualized_bps.is_finite(), format!("{at}.target_annualized_bps"), "must be a finite number");
+            out.check(
+                f.interval_sec > 0 && 86_400 % f.interval_sec == 0,
+                format!("{at}.interval_sec"),
+                "must divide a day (86400) so settlements align to UTC",
+            );
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for (i, p) in self.cfd_providers.iter().enumerate() {
+            out.check(
+                CFD_PROVIDER_NAMES.contains(&p.name.as_str()),
+                format!("cfd_providers[{i}].name"),
+                format!("unknown provider `{}` (known: {})", p.name, CFD_PROVIDER_NAMES.join(", ")),
+            );
+            out.check(seen.insert(p.name.as_str()), format!("cfd_providers[{i}].name"), format!("`{}` is listed twice", p.name));
+            out.check(p.weight.is_finite() && p.weight > 0.0, format!("cfd_providers[{i}].weight"), "must be a positive number");
+            out.check(p.timeout_ms > 0, format!("cfd_providers[{i}].timeout_ms"), "must be > 0");
+        }
+        out.check(enabled > 0, "cfd_providers", "at least one provider must be enabled");
+        if let Some(c) = &self.cme {
+            out.check(
+                CME_PROVIDER_NAMES.contains(&c.name.as_str()),
+                "cme.name",
+                format!("unknown provider `{}` (known: {})", c.name, known(CME_PROVIDER_NAMES)),
+            );
+            out.check(c.timeout_ms > 0, "cme.timeout_ms", "must be > 0");
+        }
+        if let Some(v) = &self.perp_venue {
+            out.check(
+                PERP_VENUE_NAMES.contains(&v.name.as_str()),
+                "perp_venue.name",
+                format!("unknown venue `{}` (known: {})", v.name, known(PERP_VENUE_NAMES)),
+            );
+            out.check(v.timeout_ms > 0, "perp_venue.timeout_ms", "must be > 0");
+            out.check(v.impact_notional.is_finite() && v.impact_notional > 0.0, "perp_venue.impact_notional", "must be > 0");
+            out.check(v.sample_ms > 0, "perp_venue.sample_ms", "must be > 0");
+            out.check(
+                v.window_ms >= v.sample_ms,
+                "perp_venue.window_ms",
+                format!("must be >= {} (sample_ms)", v.sample_ms),
+            );
+            out.check(v.max_staleness_ms > 0, "perp_venue.max_staleness_ms", "must be > 0");
+        }
+
+        let r = &self.reputation;
+        out.check(r.window > 0, "reputation.window", "must be > 0");
+        out.check(
+            r.deviation_bps_scale.is_finite() && r.deviation_bps_scale > 0.0,
+            "reputation.deviation_bps_scale",
+            "must be > 0",
+        );
+        out.check((0.0..=1.0).contains(&r.quarantine_below), "reputation.quarantine_below", "must be in [0, 1]");
+        out.check(
+            (r.quarantine_below..=1.0).contains(&r.reinstate_above),
+            "reputation.reinstate_above",
+            format!("must be in [{}, 1] (quarantine_below)", r.quarantine_below),
+        );
+
+        out.check(self.state.save_interval_ms > 0, "state.save_interval_ms", "must be > 0");
+
+        for (sink, p) in &self.publish.policy {
+            let at = format!("publish.policy.{sink}");
+            out.check(
+                PUBLISH_SINKS.contains(&sink.as_str()),
+                at.clone(),
+                format!("unknown sink `{sink}` (known: {})", PUBLISH_SINKS.join(", ")),
+            );
+            for (key, bps) in [("deviation_bps", p.deviation_bps), ("funding_deviation_bps", p.funding_deviation_bps)] {
+                out.check(bps.map_or(true, |b| b.is_finite() && b >= 0.0), format!("{at}.{key}"), "must be >= 0");
+            }
+            out.check(p.heartbeat_ms != Some(0), format!("{at}.heartbeat_ms"), "must be > 0");
+        }
+        out.check(
+            self.publish.mode != PublishMode::Web2Cache || self.publish.web2_cache_path.is_some(),
+            "publish.web2_cache_path",
+            "required when mode = \"web2_cache\"",
+        );
+        out.check(
+            !self.metrics.enabled || cfg!(feature = "metrics"),
+            "metrics.enabled",
+            "this build was compiled without `--features metrics`",
+        );
+        out.check(!self.metrics.namespace.trim().is_empty(), "metrics.namespace", "must not be empty");
+        out.0
+    }
+
+    /// The effective config as TOML: every market fully resolved, defaults filled in.
+    pub fn explain(&self) -> anyhow::Result<String> {
+        let markets = self
+            .markets()?
+            .into_iter()
+            .map(|m| {
+                let mut t = toml::Value::try_from(&m.oracle)?;
+                if let toml::Value::Table(t) = &mut t {
+                    t.insert("funding".into(), toml::Value::try_from(&m.funding)?);
+                }
+                Ok(t)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut root = toml::value::Table::new();
+        root.insert("markets".into(), toml::Value::Array(markets));
+        root.insert("cfd_providers".into(), toml::Value::try_from(&self.cfd_providers)?);
+        if let Some(cme) = &self.cme {
+            root.insert("cme".into(), toml::Value::try_from(cme)?);
+        }
+        if let Some(venue) = &self.perp_venue {
+            root.insert("perp_venue".into(), toml::Value::try_from(venue)?);
+        }
+        root.insert("publish".into(), toml::Value::try_from(&self.publish)?);
+        root.insert("reputation".into(), toml::Value::try_from(&self.reputation)?);
+        root.insert("state".into(), toml::Value::try_from(&self.state)?);
+        root.insert("metrics".into(), toml::Value::try_from(&self.metrics)?);
+        Ok(toml::to_string_pretty(&toml::Value::Table(root))?)
+    }
+
+    /// What a hot reload from `self` to `new` would change, one `market key: old -> new`
+    /// line per parameter. Errors when the change needs a restart: the set of
+    /// markets or a market's `expo` differs. Provider/cme/perp_venue/publish/
+    /// reputation/state/metrics changes are reported but only take effect after a restart.
+    pub fn reload_diff(&self, new: &DaemonConfig) -> anyhow::Result<Vec<String>> {
+        let (old_m, new_m) = (self.markets()?, new.markets()?);
+        let symbols = |ms: &[MarketConfig]| ms.iter().map(|m| m.oracle.symbol.clone()).collect::<std::collections::BTreeSet<_>>();
+        if symbols(&old_m) != symbols(&new_m) {
+            bail!("adding or removing markets needs a restart");
+        }
+        let mut out = Vec::new();
+        for n in &new_m {
+            let o = old_m.iter().find(|o| o.oracle.symbol == n.oracle.symbol).unwrap();
+            if o.oracle.expo != n.oracle.expo {
+                bail!("{}: changing expo needs a restart", n.oracle.symbol);
+            }
+            for line in diff(&o.oracle, &n.oracle).into_iter().chain(diff(&o.funding, &n.funding).into_iter().map(|l| format!("funding.{l}"))) {
+                out.push(format!("{} {line}", n.oracle.symbol));
+            }
+        }
+        if self.cfd_providers != new.cfd_providers {
+            out.push("cfd_providers changed (applies after restart)".into());
+        }
+        if self.cme != new.cme {
+            out.push("cme changed (applies after restart)".into());
+        }
+        if self.perp_venue != new.perp_venue {
+            out.push("perp_venue changed (applies after restart)".into());
+        }
+        if self.publish != new.publish {
+            out.push("publish changed (applies after restart)".into());
+        }
+        if self.reputation != new.reputation {
+            out.push("reputation changed (applies after restart)".into());
+        }
+        if self.state != new.state {
+            out.push("state changed (applies after restart)".into());
+        }
+        if self.metrics != new.metrics {
+            out.push("metrics changed (applies after restart)".into());
+        }
+        Ok(out)
+    }
+
+    /// The config in effect once `new` has been hot-applied: its market settings
+    /// (`reload_diff` has already refused anything `Oracle::apply_config` would
+    /// reject), with every restart-only section kept from `self`, so those
+    /// changes are reported again on the next reload until a restart.
+    pub fn applied(&self, new: &DaemonConfig) -> DaemonConfig {
+        DaemonConfig { oracle: new.oracle.clone(), markets: new.markets.clone(), funding: new.funding.clone(), ..self.clone() }
+    }
+
+    pub fn enabled_providers(&self) -> impl Iterator<Item = &CfdProviderConfig> {
+        self.cfd_providers.iter().filter(|p| p.enabled)
+    }
+}
+
+/// One market's resolved settings.
+#[derive(Debug, Clone)]
+pub struct MarketConfig {
+    pub oracle: OracleConfig,
+    pub funding: FundingConfig,
+}
+
+/// `key: old -> new` for every top-level key whose value differs.
+pub fn diff<T: Serialize>(old: &T, new: &T) -> Vec<String> {
+    let (Ok(toml::Value::Table(old)), Ok(toml::Value::Table(new))) = (toml::Value::try_from(old), toml::Value::try_from(new)) else {
+        return Vec::new();
+    };
+    new.iter()
+        .filter_map(|(k, v)| match old.get(k) {
+            Some(o) if o == v => None,
+            Some(o) => Some(format!("{k}: {o} -> {v}")),
+            None => Some(format!("{k}: (unset) -> {v}")),
+        })
+        .collect()
+}
+
+/// A list of accepted names for an error message.
+fn known(names: &[&str]) -> String {
+    if names.is_empty() { "none".into() } else { names.join(", ") }
+}
+
+/// `base` with the keys of `over` replaced.
+fn overlay(base: &toml::Value, over: toml::value::Table) -> toml::Value {
+    let mut out = base.clone();
+    if let toml::Value::Table(t) = &mut out {
+        t.extend(over);
+    }
+    out
+}
+
+/// Accepted `hours_guard` values.
+pub const HOURS_GUARDS: &[&str] = &["off", "vendor", "cme"];
+
+/// One validation problem; `path` is the dotted key, e.g. `markets[1].expo`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
     }
 }
 
+#[derive(Default)]
+struct Issues(Vec<ConfigIssue>);
+
+impl Issues {
+    fn push(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigIssue { path: path.into(), message: message.into() });
+    }
+    fn check(&mut self, ok: bool, path: impl Into<String>, message: impl Into<String>) {
+        if !ok {
+            self.push(path, message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_config_parses() {
+        let cfg = DaemonConfig::from_toml_str(include_str!("../config/oracle.example.toml")).unwrap();
+        assert_eq!(cfg.oracle.symbol, "LEAN_HOGS_PERP");
+        assert_eq!(cfg.oracle.expo, -8);
+        assert_eq!(cfg.oracle.tick_interval_ms, 1000);
+        assert_eq!(cfg.cfd_providers.len(), 2);
+        assert_eq!(cfg.cfd_providers[0].timeout_ms, 850);
+        assert_eq!(cfg.oracle.circuit_breaker_windows[1], BreakerWindow { secs: 900, max_move: 0.15 });
+        assert_eq!(cfg.publish.mode, PublishMode::Web2Cache);
+        assert_eq!(cfg.funding.ema_alpha, 0.005);
+        assert!((cfg.funding.cap_per_interval() - 0.0064).abs() < 1e-12);
+        assert!(!cfg.metrics.enabled);
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let base = include_str!("../config/oracle.example.toml");
+        let err = DaemonConfig::from_toml_str(&base.replace("expo = -8", "expo = -6")).unwrap_err();
+        assert!(err.to_string().starts_with("oracle.expo"), "{err}");
+        assert!(DaemonConfig::from_toml_str(&base.replace("cfd_mad_k", "cfd_mad_kk")).is_err());
+        let err = DaemonConfig::from_toml_str(&base.replace("\"openwebninja\"", "\"brokerX\"")).unwrap_err();
+        assert!(err.to_string().starts_with("cfd_providers[1].name"), "{err}");
+        let venue = DaemonConfig::from_toml_str(&format!("{base}\n[perp_venue]\nname = \"dummy\"\n")).unwrap().perp_venue.unwrap();
+        assert_eq!((venue.sample_ms, venue.window_ms), (1_000, 60_000));
+        let err = DaemonConfig::from_toml_str(&format!("{base}\n[perp_venue]\nname = \"dummy\"\nwindow_ms = 500\n")).unwrap_err();
+        assert!(err.to_string().starts_with("perp_venue.window_ms"), "{err}");
+        let err = DaemonConfig::from_toml_str(&format!("{base}\n[publish.policy.kafka]\nheartbeat_ms = 1000\n")).unwrap_err();
+        assert!(err.to_string().starts_with("publish.policy.kafka: unknown sink"), "{err}");
+        // Funding lives in [funding] and staleness derives from cfd_tau_ms; the old [oracle] keys are gone.
+        assert!(DaemonConfig::from_toml_str(&base.replace("[oracle]\n", "[oracle]\nfunding_kappa = 0.5\n")).is_err());
+        let err = DaemonConfig::from_toml_str(&base.replace("ema_alpha             = 0.005", "ema_alpha = 0.005\nmax_change_bps_per_hour = 0.0")).unwrap_err();
+        assert!(err.to_string().starts_with("funding.max_change_bps_per_hour"), "{err}");
+    }
+
+    #[test]
+    fn markets_override_oracle_and_funding_defaults() {
+        let base = include_str!("../config/oracle.example.toml");
+        let cfg = DaemonConfig::from_toml_str(&format!(
+            "{base}\n[[markets]]\nsymbol = \"GOLD_PERP\"\ncfd_mad_k = 5.0\n[markets.funding]\nkappa = 0.05\n\n\
+             [[markets]]\nsymbol = \"CORN_PERP\"\nexpo = -10\n"
+        ))
+        .unwrap();
+        let markets = cfg.markets().unwrap();
+        assert_eq!(markets.len(), 2);
+        assert_eq!((markets[0].oracle.symbol.as_str(), markets[0].oracle.cfd_mad_k), ("GOLD_PERP", 5.0));
+        assert_eq!((markets[0].funding.kappa, markets[0].funding.ema_alpha), (0.05, 0.005));
+        assert_eq!((markets[1].oracle.expo, markets[1].oracle.cfd_mad_k), (-10, 3.5));
+        assert_eq!(markets[1].oracle.tick_interval_ms, 1000);
+
+        let err = DaemonConfig::from_toml_str(&format!("{base}\n[[markets]]\nsymbol = \"GOLD_PERP\"\nexpo = 3\n")).unwrap_err();
+        assert!(err.to_string().starts_with("markets[0].expo"), "{err}");
+    }
+
+    #[test]
+    fn reports_every_issue_with_its_path() {
+        let base = include_str!("../config/oracle.example.toml");
+        let bad = base
+            .replace("cfd_tau_ms = 8000", "cfd_tau_ms = 0")
+            .replace("hours_guard = \"vendor\"", "hours_guard = \"always\"")
+            .replace("cfd_min_fresh = 2", "cfd_min_fresh = 3");
+        let cfg: DaemonConfig = toml::from_str(&bad).unwrap();
+        let paths: Vec<String> = cfg.issues().into_iter().map(|i| i.path).collect();
+        assert_eq!(paths, ["oracle.cfd_tau_ms", "oracle.cfd_min_fresh", "oracle.hours_guard"]);
+        assert_eq!(DaemonConfig::from_toml_str(&bad).unwrap_err().to_string().lines().count(), 3);
+
+        let cfg: DaemonConfig = toml::from_str(&format!("{base}\n[[markets]]\nsymbol = \"GOLD_PERP\"\ncfd_mad_kk = 1.0\n")).unwrap();
+        let issues = cfg.issues();
+        assert_eq!(issues[0].path, "markets[0]");
+        assert!(issues[0].message.contains("cfd_mad_kk"), "{}", issues[0]);
+    }
+
+    #[test]
+    fn explain_prints_resolved_defaults() {
+        let cfg = DaemonConfig::from_toml_str(include_str!("../config/oracle.example.toml")).unwrap();
+        let out = cfg.explain().unwrap();
+        let back: toml::Value = toml::from_str(&out).unwrap();
+        let m = &back["markets"][0];
+        assert_eq!(m["symbol"].as_str(), Some("LEAN_HOGS_PERP"));
+        assert_eq!(m["cme_max_staleness_ms"].as_integer(), Some(120_000)); // default, not in the file
+        assert_eq!(m["funding"]["kappa"].as_float(), Some(0.02));
+        assert_eq!(m["circuit_breaker_windows"][0]["secs"].as_integer(), Some(300));
+        assert_eq!(back["publish"]["mode"].as_str(), Some("web2_cache"));
+    }
+
+    #[test]
+    fn reload_diff_lists_changes_and_rejects_restart_only_ones() {
+        let base = include_str!("../config/oracle.example.toml");
+        let old = DaemonConfig::from_toml_str(base).unwrap();
+        let new = DaemonConfig::from_toml_str(&base.replace("cfd_mad_k = 3.5", "cfd_mad_k = 5.0").replace("ema_alpha             = 0.005", "ema_alpha = 0.01")).unwrap();
+        assert_eq!(old.reload_diff(&new).unwrap(), vec![
+            "LEAN_HOGS_PERP cfd_mad_k: 3.5 -> 5.0".to_string(),
+            "LEAN_HOGS_PERP funding.ema_alpha: 0.005 -> 0.01".to_string(),
+        ]);
+        assert!(old.reload_diff(&old).unwrap().is_empty());
+
+        // Restart-only changes stay pending until a restart.
+        let republished = DaemonConfig::from_toml_str(&base.replace("cfd_mad_k = 3.5", "cfd_mad_k = 5.0").replace("mode = \"web2_cache\"", "mode = \"stdout\"")).unwrap();
+        let current = old.applied(&republished);
+        assert_eq!(current.reload_diff(&republished).unwrap(), ["publish changed (applies after restart)"]);
+
+        let moved = DaemonConfig::from_toml_str(&base.replace("LEAN_HOGS_PERP", "CORN_PERP")).unwrap();
+        assert!(old.reload_diff(&moved).is_err());
+        let rescaled = DaemonConfig::from_toml_str(&base.replace("expo = -8", "expo = -10")).unwrap();
+        assert!(old.reload_diff(&rescaled).is_err());
+    }
+}
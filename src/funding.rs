@@ -1,28 +1,160 @@
 // src/funding.rs
-use crate::types::{FundingUpdate, IndexTick};
+use serde::{Deserialize, Serialize};
 
+use crate::config::FundingConfig;
+use crate::types::{FundingKind, FundingUpdate};
+
+/// Funding per interval from the interval's average basis:
+///
+/// `rate = P + clamp(I − P, −c, c)` with the premium term `P = kappa · basis`,
+/// the interest baseline `I` and the premium clamp `c`, so a premium within `c`
+/// of the baseline pays just the baseline. The result then moves at most
+/// `max_change` from the previous settled rate and is capped at `±cap`. All
+/// rates are per interval (see `from_config` for the per-hour knobs).
+/// `ema_alpha` is for the oracle's reference EMA of the mark, the basis when
+/// there is no premium index.
 pub struct FundingEngine {
     pub kappa: f64,
     pub cap: f64,
     pub interval_sec: u32,
+    pub interest: f64,
+    pub premium_clamp: f64,
+    pub max_change: Option<f64>,
+    pub ema_alpha: f64,
 }
 
 impl FundingEngine {
-    pub fn new(kappa: f64, cap: f64, interval_sec: u32) -> Self { Self { kappa, cap, interval_sec } }
+    /// Premium-only funding: no interest baseline, clamp or change limit, and
+    /// the default reference EMA.
+    pub fn new(kappa: f64, cap: f64, interval_sec: u32) -> Self {
+        let ema_alpha = FundingConfig::default().ema_alpha;
+        Self { kappa, cap, interval_sec, interest: 0.0, premium_clamp: 0.0, max_change: None, ema_alpha }
+    }
+
+    pub fn from_config(f: &FundingConfig) -> Self {
+        Self {
+            kappa: f.kappa,
+            cap: f.cap_per_interval(),
+            interval_sec: f.interval_sec,
+            interest: f.interest_per_interval(),
+            premium_clamp: f.premium_clamp_per_interval(),
+            max_change: f.max_change_per_interval(),
+            ema_alpha: f.ema_alpha,
+        }
+    }
 
-    pub fn compute(&self, mark: &IndexTick, index_ref: &IndexTick) -> FundingUpdate {
-        let basis = (mark.price - index_ref.price) / index_ref.price;
-        let raw = self.kappa * basis;
-        let rate = raw.clamp(-self.cap, self.cap);
+    /// Funding per interval for `basis`, a fraction of the index (e.g. the
+    /// interval's average premium, see `FundingAccumulator`), given the
+    /// `previous` settled rate.
+    pub fn rate(&self, basis: f64, previous: Option<f64>) -> f64 {
+        let premium = self.kappa * basis;
+        let mut rate = premium + (self.interest - premium).clamp(-self.premium_clamp, self.premium_clamp);
+        if let (Some(prev), Some(step)) = (previous, self.max_change) {
+            rate = rate.clamp(prev - step, prev + step);
+        }
+        rate.clamp(-self.cap, self.cap)
+    }
+
+    /// The update for `symbol`'s market at `settle_ms`.
+    pub fn update(&self, symbol: &str, basis: f64, previous: Option<f64>, kind: FundingKind, settle_ms: i64, ts_ms: i64) -> FundingUpdate {
         FundingUpdate {
-            symbol: format!("{}-PERP", mark.symbol),
-            rate,
+            symbol: format!("{symbol}-PERP"),
+            rate: self.rate(basis, previous),
             interval_sec: self.interval_sec,
-            ts_ms: mark.ts_ms,
+            ts_ms,
+            kind,
+            settle_ms,
         }
     }
 }
 
+/// Time-weighted average basis over one funding interval.
+///
+/// Intervals are aligned to the UTC day (an 8h interval settles at 00:00, 08:00
+/// and 16:00). Each sample holds until the next one, so the average weights
+/// every basis by how long it stood. An interval is settled by the first
+/// sample at or after its end; the last sample before the boundary holds up to
+/// it and also opens the next interval.
+#[derive(Debug, Clone)]
+pub struct FundingAccumulator {
+    pub interval_sec: u32,
+    state: AccumulatorState,
+}
+
+/// A settled interval.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Settlement {
+    pub settle_ms: i64,
+    pub twap: f64,
+    pub covered_ms: i64, // how much of the interval had samples
+}
+
+/// What `FundingAccumulator` needs to carry on after a restart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccumulatorState {
+    pub interval_start_ms: Option<i64>,
+    pub last: Option<(i64, f64)>, // (ts_ms, basis)
+    pub weighted: f64,            // Σ basis·ms since `interval_start_ms`
+    pub covered_ms: i64,
+}
+
+impl FundingAccumulator {
+    pub fn new(interval_sec: u32) -> Self { Self { interval_sec, state: AccumulatorState::default() } }
+
+    fn interval_ms(&self) -> i64 { self.interval_sec.max(1) as i64 * 1000 }
+
+    /// Start of the interval containing `ts_ms`.
+    pub fn interval_start(&self, ts_ms: i64) -> i64 { ts_ms - ts_ms.rem_euclid(self.interval_ms()) }
+
+    /// The settlement after `ts_ms`.
+    pub fn next_settle_ms(&self, ts_ms: i64) -> i64 { self.interval_start(ts_ms) + self.interval_ms() }
+
+    /// Add `basis` at `ts_ms`. Returns the interval it closed, if it crossed a boundary.
+    pub fn observe(&mut self, basis: f64, ts_ms: i64) -> Option<Settlement> {
+        let (start, interval_ms) = (self.interval_start(ts_ms), self.interval_ms());
+        let s = &mut self.state;
+        let mut settled = None;
+        if let (Some(open), Some((last_ts, last_basis))) = (s.interval_start_ms, s.last) {
+            if open < start {
+                let end = open + interval_ms;
+                let held = end - last_ts;
+                let covered = s.covered_ms + held;
+                if covered > 0 {
+                    let twap = (s.weighted + last_basis * held as f64) / covered as f64;
+                    settled = Some(Settlement { settle_ms: end, twap, covered_ms: covered });
+                }
+                // The last sample carries into the new interval only if that is the one right after.
+                (s.weighted, s.covered_ms) = (0.0, 0);
+                s.last = (end == start).then_some((start, last_basis));
+            }
+        }
+        if let Some((last_ts, last_basis)) = s.last {
+            let held = (ts_ms - last_ts).max(0);
+            s.weighted += last_basis * held as f64;
+            s.covered_ms += held;
+        }
+        s.interval_start_ms = Some(start);
+        s.last = Some((ts_ms, basis));
+        settled
+    }
+
+    /// Average basis so far this interval, counting the last sample up to `now_ms`.
+    pub fn twap(&self, now_ms: i64) -> Option<f64> {
+        let s = &self.state;
+        let (last_ts, last_basis) = s.last?;
+        if s.interval_start_ms != Some(self.interval_start(now_ms)) {
+            return Some(last_basis);
+        }
+        let held = (now_ms - last_ts).max(0);
+        let covered = s.covered_ms + held;
+        Some(if covered > 0 { (s.weighted + last_basis * held as f64) / covered as f64 } else { last_basis })
+    }
+
+    pub fn state(&self) -> AccumulatorState { self.state }
+
+    pub fn restore(&mut self, state: AccumulatorState) { self.state = state; }
+}
+
 pub struct Ema {
     pub alpha: f64,       // 2/(N+1). For 60-min ref sampled per second, pick alpha≈2/3601
     pub value: Option<f64>,
@@ -38,3 +170,68 @@ impl Ema {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const H: i64 = 3_600_000;
+    const DAY: i64 = 1_700_006_400_000; // 2023-11-15 00:00 UTC
+
+    #[test]
+    fn settles_a_time_weighted_premium_at_utc_boundaries() {
+        let mut acc = FundingAccumulator::new(8 * 3600);
+        assert_eq!(acc.next_settle_ms(DAY + 5 * H), DAY + 8 * H);
+        assert_eq!(acc.next_settle_ms(DAY + 8 * H), DAY + 16 * H);
+
+        // Starts mid-interval: 1h at 0.001, then 2h at 0.004.
+        assert_eq!(acc.observe(0.001, DAY + 5 * H), None);
+        assert_eq!(acc.observe(0.004, DAY + 6 * H), None);
+        assert!((acc.twap(DAY + 7 * H).unwrap() - 0.0025).abs() < 1e-12); // so far: 1h each
+        let s = acc.observe(0.0, DAY + 8 * H + 1_000).unwrap();
+        assert_eq!((s.settle_ms, s.covered_ms), (DAY + 8 * H, 3 * H));
+        assert!((s.twap - 0.003).abs() < 1e-12);
+
+        // 0.004 held over the boundary for 1s, then 0 for the rest.
+        let s = acc.observe(0.0, DAY + 16 * H).unwrap();
+        assert_eq!((s.settle_ms, s.covered_ms), (DAY + 16 * H, 8 * H));
+        assert!((s.twap - 0.004 * 1_000.0 / (8 * H) as f64).abs() < 1e-12);
+
+        // A gap over a whole interval settles what there was and starts afresh.
+        let s = acc.observe(0.002, DAY + 40 * H).unwrap();
+        assert_eq!(s.settle_ms, DAY + 24 * H);
+        assert_eq!(acc.twap(DAY + 41 * H), Some(0.002));
+
+        let mut back = FundingAccumulator::new(8 * 3600);
+        back.restore(acc.state());
+        assert_eq!(back.twap(DAY + 41 * H), acc.twap(DAY + 41 * H));
+    }
+
+    #[test]
+    fn rate_clamps_the_premium_around_the_interest_baseline() {
+        let f = FundingConfig {
+            target_annualized_bps: 1095.0,        // 1 bp per 8h
+            clamp_bps_per_hour: 1.0,              // 8 bps per 8h
+            premium_clamp_bps_per_hour: 0.625,    // 5 bps per 8h
+            max_change_bps_per_hour: Some(0.25),  // 2 bps per 8h
+            kappa: 1.0,
+            ..FundingConfig::default()
+        };
+        let e = FundingEngine::from_config(&f);
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-12;
+        assert!(close(e.interest, 0.0001) && close(e.premium_clamp, 0.0005) && close(e.cap, 0.0008));
+
+        // Within 5 bps of the baseline: just the baseline.
+        assert!(close(e.rate(0.0004, None), 0.0001));
+        assert!(close(e.rate(-0.0003, None), 0.0001));
+        // Beyond it: the premium less the clamp, then the cap.
+        assert!(close(e.rate(0.0009, None), 0.0004));
+        assert!(close(e.rate(-0.0009, None), -0.0004));
+        assert!(close(e.rate(0.01, None), 0.0008));
+        // At most 2 bps away from the previous settlement.
+        assert!(close(e.rate(0.0009, Some(0.0001)), 0.0003));
+        assert!(close(e.rate(0.01, Some(0.0007)), 0.0008));
+
+        // The plain engine is premium-only.
+        assert!(close(FundingEngine::new(0.02, 0.005, 3600).rate(0.005, Some(0.0)), 0.0001));
+    }
+}
@@ -0,0 +1,141 @@
+// src/health.rs
+//! Per-market health, re-evaluated every tick and published with the mark.
+//!
+//!   Healthy     fresh consensus mark, nothing unusual
+//!   Degraded    fresh mark, but with caveats (wide dispersion, step clamp, missing or
+//!               quarantined providers)
+//!   Stale       no usable consensus this tick; no new price is published
+//!   Halted      hours closed (nothing published) or breaker tripped (the last good
+//!               mark, if any, is republished frozen with its old timestamp)
+//!   Recovering  fresh marks again after Stale/Halted, until `RECOVERY_TICKS` in a row
+
+use serde::{Deserialize, Serialize};
+
+/// Consecutive good ticks needed to leave `Recovering`.
+pub const RECOVERY_TICKS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Stale,
+    Halted,
+    Recovering,
+}
+
+impl HealthStatus {
+    /// Whether this tick's mark is a fresh price (as opposed to frozen or withheld).
+    pub fn is_live(self) -> bool {
+        matches!(self, HealthStatus::Healthy | HealthStatus::Degraded | HealthStatus::Recovering)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthReason {
+    HoursClosed,
+    NotEnoughFresh,
+    ConsensusFailed,
+    WideDispersion,
+    StepClamped,
+    ProvidersMissing,
+    ProvidersQuarantined,
+    BreakerTripped,
+    NoPriorMark,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OracleHealth {
+    pub symbol: String,
+    pub status: HealthStatus,
+    pub reasons: Vec<HealthReason>,
+    pub since_ms: i64,             // when `status` was entered
+    pub last_mark_ms: Option<i64>, // ts of the last fresh mark
+    pub ts_ms: i64,
+}
+
+/// What a tick ended up doing, before health is derived from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickOutcome {
+    Marked,
+    Stale,
+    Halted,
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthTracker {
+    status: HealthStatus,
+    since_ms: i64,
+    good_streak: u32,
+    last_mark_ms: Option<i64>,
+}
+
+impl Default for HealthTracker {
+    fn default() -> Self {
+        // Nothing has been marked yet, so the first good ticks count as recovery.
+        Self { status: HealthStatus::Stale, since_ms: 0, good_streak: 0, last_mark_ms: None }
+    }
+}
+
+impl HealthTracker {
+    pub fn status(&self) -> HealthStatus { self.status }
+
+    pub fn observe(&mut self, symbol: &str, outcome: TickOutcome, reasons: Vec<HealthReason>, now_ms: i64) -> OracleHealth {
+        let next = match outcome {
+            TickOutcome::Stale => {
+                self.good_streak = 0;
+                HealthStatus::Stale
+            }
+            TickOutcome::Halted => {
+                self.good_streak = 0;
+                HealthStatus::Halted
+            }
+            TickOutcome::Marked => {
+                self.good_streak = self.good_streak.saturating_add(1);
+                self.last_mark_ms = Some(now_ms);
+                let recovering = matches!(self.status, HealthStatus::Stale | HealthStatus::Halted | HealthStatus::Recovering);
+                if recovering && self.good_streak < RECOVERY_TICKS {
+                    HealthStatus::Recovering
+                } else if reasons.is_empty() {
+                    HealthStatus::Healthy
+                } else {
+                    HealthStatus::Degraded
+                }
+            }
+        };
+        if next != self.status {
+            self.status = next;
+            self.since_ms = now_ms;
+        }
+        OracleHealth {
+            symbol: symbol.to_string(),
+            status: self.status,
+            reasons,
+            since_ms: self.since_ms,
+            last_mark_ms: self.last_mark_ms,
+            ts_ms: now_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use HealthStatus::*;
+
+    #[test]
+    fn recovers_after_consecutive_good_ticks() {
+        let mut h = HealthTracker::default();
+        let mut step = |outcome, reasons: Vec<HealthReason>, t| h.observe("CORN_PERP", outcome, reasons, t).status;
+        assert_eq!(step(TickOutcome::Marked, vec![], 1), Recovering);
+        assert_eq!(step(TickOutcome::Marked, vec![], 2), Recovering);
+        assert_eq!(step(TickOutcome::Marked, vec![], 3), Healthy);
+        assert_eq!(step(TickOutcome::Marked, vec![HealthReason::WideDispersion], 4), Degraded);
+        assert_eq!(step(TickOutcome::Stale, vec![HealthReason::NotEnoughFresh], 5), Stale);
+        assert_eq!(step(TickOutcome::Marked, vec![], 6), Recovering);
+        assert_eq!(step(TickOutcome::Halted, vec![HealthReason::BreakerTripped], 7), Halted);
+        let last = h.observe("CORN_PERP", TickOutcome::Halted, vec![HealthReason::BreakerTripped], 8);
+        assert_eq!((last.since_ms, last.last_mark_ms), (7, Some(6)));
+    }
+}
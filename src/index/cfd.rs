@@ -12,6 +12,12 @@ pub struct CfdIndexBuilder {
     last_px: Option<f64>,
 }
 
+impl Default for CfdIndexBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CfdIndexBuilder {
     pub fn new() -> Self {
         Self {
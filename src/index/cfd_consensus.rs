@@ -1,21 +1,36 @@
 // src/index/cfd_consensus.rs
+use std::collections::HashMap;
+
+use crate::clock::{self, SharedClock};
 use crate::index::IndexError;
 use crate::types::{CfdQuote, ConsensusStats, IndexTick};
 
 /// Robust consensus over CFD quotes:
 /// 1) median anchor
 /// 2) MAD outlier rejection
-/// 3) freshness-weighted mean around the median
+/// 3) freshness-weighted mean around the median, scaled by per-source weights
 pub struct CfdConsensus {
     pub symbol: String,
     pub expo: i8,
     pub tau_ms: u64,
     pub mad_k: f64, // keep quotes within ± mad_k * MAD around median
+    pub clock: SharedClock,
+    pub weights: HashMap<String, f64>, // by `CfdSource::name()`; missing = 1.0
 }
 
 impl CfdConsensus {
     pub fn new<S: Into<String>>(symbol: S, expo: i8, tau_ms: u64, mad_k: f64) -> Self {
-        Self { symbol: symbol.into(), expo, tau_ms, mad_k }
+        Self { symbol: symbol.into(), expo, tau_ms, mad_k, clock: clock::system(), weights: HashMap::new() }
+    }
+
+    pub fn with_weights(mut self, weights: HashMap<String, f64>) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
     }
 
     fn median(prices: &mut [f64]) -> f64 {
@@ -31,10 +46,15 @@ impl CfdConsensus {
     }
 
     pub fn build(&self, quotes: &[CfdQuote]) -> Result<(IndexTick, ConsensusStats), IndexError> {
+        self.build_detailed(quotes).map(|(tick, stats, _)| (tick, stats))
+    }
+
+    /// `build`, plus what happened to each input quote.
+    pub fn build_detailed(&self, quotes: &[CfdQuote]) -> Result<(IndexTick, ConsensusStats, ConsensusDetail), IndexError> {
         if quotes.is_empty() {
             return Err(IndexError::NotEnoughData);
         }
-        let now = chrono::Utc::now().timestamp_millis();
+        let now = self.clock.now_ms();
 
         // anchor on median
         let mut ps: Vec<f64> = quotes.iter().map(|q| q.price).collect();
@@ -44,10 +64,13 @@ impl CfdConsensus {
         // outlier filter
         let band = self.mad_k * mad;
         let mut kept = Vec::new();
+        let mut kept_mask = Vec::with_capacity(quotes.len());
         let mut minp = f64::INFINITY;
         let mut maxp = f64::NEG_INFINITY;
         for q in quotes {
-            if (q.price - med).abs() <= band {
+            let keep = (q.price - med).abs() <= band;
+            kept_mask.push(keep);
+            if keep {
                 kept.push(q);
                 if q.price < minp { minp = q.price; }
                 if q.price > maxp { maxp = q.price; }
@@ -60,19 +83,32 @@ impl CfdConsensus {
         // freshness-weighted average around median
         let mut num = 0.0;
         let mut den = 0.0;
+        let mut weights = Vec::with_capacity(kept.len());
         for q in &kept {
             let age = (now - q.ts_ms).unsigned_abs() as f64;
             let w = f64::exp(-age / self.tau_ms as f64);
             let dev = ((q.price - med).abs() / (mad + 1e-9)).min(10.0);
-            let w2 = w * f64::exp(-0.15 * dev);
+            let src_w = self.weights.get(q.src.name()).copied().unwrap_or(1.0);
+            let w2 = src_w * w * f64::exp(-0.15 * dev);
             num += w2 * q.price;
             den += w2;
+            weights.push(w2);
         }
         if den <= 0.0 {
             return Err(IndexError::NotEnoughData);
         }
         let fused = num / den;
 
+        // confidence band (price units): robust sigma of all quotes, or the
+        // weighted dispersion of the kept ones if that is wider
+        let var = kept
+            .iter()
+            .zip(&weights)
+            .map(|(q, w)| w * (q.price - fused).powi(2))
+            .sum::<f64>()
+            / den;
+        let conf = mad.max(var.sqrt());
+
         let spread_bps = (((maxp - minp) / med).abs() * 10_000.0).round() as u32;
         let confidence = {
             let n = kept.len() as f32 / (quotes.len().max(1) as f32);
@@ -94,7 +130,61 @@ impl CfdConsensus {
             n_dropped: quotes.len().saturating_sub(kept.len()),
             spread_bps,
             confidence,
+            conf,
+        };
+        let mut kept_weights = weights.into_iter();
+        let detail = ConsensusDetail {
+            median: med,
+            mad,
+            band,
+            weights: kept_mask.iter().map(|&k| if k { kept_weights.next().unwrap_or(0.0) } else { 0.0 }).collect(),
+            kept: kept_mask,
         };
-        Ok((tick, stats))
+        Ok((tick, stats, detail))
+    }
+}
+
+/// Per-quote outcome of `build_detailed`, indexed like the input.
+#[derive(Debug, Clone)]
+pub struct ConsensusDetail {
+    pub median: f64,
+    pub mad: f64,
+    pub band: f64,        // quotes further than this from the median were dropped
+    pub kept: Vec<bool>,
+    pub weights: Vec<f64>, // final (unnormalised) weight; 0 for dropped quotes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::types::CfdSource;
+    use std::sync::Arc;
+
+    const T: i64 = 1_700_000_000_000;
+
+    fn q(price: f64, ts_ms: i64) -> CfdQuote {
+        CfdQuote { src: CfdSource::Other("test".into()), price, ts_ms }
+    }
+
+    #[test]
+    fn freshness_weights_follow_the_injected_clock() {
+        let clock = ManualClock::new(T);
+        let c = CfdConsensus::new("CORN_PERP", -8, 8_000, 3.5).with_clock(Arc::new(clock.clone()));
+
+        // The fresher quote pulls the mark towards itself.
+        let (a, _) = c.build(&[q(100.0, T), q(102.0, T - 8_000)]).unwrap();
+        let (b, _) = c.build(&[q(100.0, T - 8_000), q(102.0, T)]).unwrap();
+        assert_eq!(a.ts_ms, T);
+        assert!(a.price < 101.0 && b.price > 101.0, "{} {}", a.price, b.price);
+
+        // Same inputs at the same virtual time replay exactly.
+        assert_eq!(c.build(&[q(100.0, T), q(102.0, T - 8_000)]).unwrap().0.price, a.price);
+
+        // Ageing both quotes equally leaves the relative weights unchanged.
+        clock.advance_ms(60_000);
+        let (later, _) = c.build(&[q(100.0, T), q(102.0, T - 8_000)]).unwrap();
+        assert_eq!(later.ts_ms, T + 60_000);
+        assert!((later.price - a.price).abs() < 1e-9);
     }
 }
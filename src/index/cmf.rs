@@ -1,6 +1,7 @@
 // src/index/cmf.rs
 
 use super::{IndexBuilder, IndexError};
+use crate::clock::{self, SharedClock};
 use crate::types::{IndexTick, CmfInputs};
 
 /// Constant-Maturity Futures (CMF) over two adjacent expiries.
@@ -8,11 +9,17 @@ use crate::types::{IndexTick, CmfInputs};
 pub struct CmfIndexBuilder {
     pub symbol: String,
     pub expo: i8,          // e.g. -8 for 1e-8 scaling
+    pub clock: SharedClock,
 }
 
 impl CmfIndexBuilder {
     pub fn new<S: Into<String>>(symbol: S, expo: i8) -> Self {
-        Self { symbol: symbol.into(), expo }
+        Self { symbol: symbol.into(), expo, clock: clock::system() }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
     }
 
     /// Days between `now_ms` and `future_ms` (non-negative, in fractional days).
@@ -25,8 +32,8 @@ impl CmfIndexBuilder {
 
 impl IndexBuilder<CmfInputs> for CmfIndexBuilder {
     fn build(&mut self, tick: CmfInputs) -> Result<IndexTick, IndexError> {
-        // Use wall clock for time-to-expiry math
-        let now_ms = chrono::Utc::now().timestamp_millis();
+        // Time-to-expiry is measured from the builder's clock
+        let now_ms = self.clock.now_ms();
 
         // Compute time-to-expiry (days) for the two legs
         let mut d1 = Self::days_to(now_ms, tick.f1.expiry_ts_ms);
@@ -85,3 +92,26 @@ impl IndexBuilder<CmfInputs> for CmfIndexBuilder {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::types::FuturesLeg;
+    use std::sync::Arc;
+
+    #[test]
+    fn interpolates_from_clock_time() {
+        const DAY: i64 = 86_400_000;
+        let t = 1_700_000_000_000;
+        let mut b = CmfIndexBuilder::new("WTI_PERP", -8).with_clock(Arc::new(ManualClock::new(t)));
+        let inputs = CmfInputs {
+            f1: FuturesLeg { price: 100.0, ts_ms: t, expiry_ts_ms: t + 10 * DAY },
+            f2: FuturesLeg { price: 130.0, ts_ms: t, expiry_ts_ms: t + 40 * DAY },
+            target_days: 30.0,
+        };
+        let tick = b.build(inputs).unwrap();
+        assert_eq!(tick.ts_ms, t);
+        assert!((tick.price - 120.0).abs() < 1e-9, "{}", tick.price);
+    }
+}
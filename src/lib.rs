@@ -1,5 +1,6 @@
 // src/lib.rs
 pub mod types;
+pub mod clock;
 pub mod config;
 pub mod metrics;
 pub mod publishing;
@@ -7,5 +8,14 @@ pub mod providers;
 pub mod index;
 pub mod risk;
 pub mod funding;
+pub mod premium;
+pub mod health;
+pub mod reputation;
+pub mod audit;
 pub mod oracle;
+pub mod price_feed;
+pub mod rounds;
+pub mod state;
+pub mod server;
+pub mod signing;
 
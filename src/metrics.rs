@@ -4,7 +4,8 @@
 mod imp {
     use once_cell::sync::Lazy;
     use prometheus::{
-        register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec,
+        register_gauge_vec, register_histogram_vec, register_int_counter_vec, GaugeVec, HistogramVec,
+        IntCounterVec,
     };
 
     pub static REQUESTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -34,6 +35,15 @@ mod imp {
             .unwrap()
     });
 
+    pub static PROVIDER_LATE_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+        register_int_counter_vec!(
+            "provider_late_total",
+            "Provider quotes that missed their collection deadline",
+            &["provider"]
+        )
+            .unwrap()
+    });
+
     pub static PROVIDER_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
         register_histogram_vec!(
             "provider_latency_seconds",
@@ -44,6 +54,51 @@ mod imp {
             .unwrap()
     });
 
+    pub static PROVIDER_SCORE: Lazy<GaugeVec> = Lazy::new(|| {
+        register_gauge_vec!(
+            "provider_score",
+            "Provider reputation score (0..1) per market",
+            &["symbol", "provider"]
+        )
+            .unwrap()
+    });
+
+    pub static PROVIDER_QUARANTINE_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+        register_int_counter_vec!(
+            "provider_quarantine_total",
+            "Provider quarantine state changes",
+            &["symbol", "provider", "event"]
+        )
+            .unwrap()
+    });
+
+    pub static PUBLISH_SUPPRESSED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+        register_int_counter_vec!(
+            "publish_suppressed_total",
+            "Updates held back by a sink's publish policy",
+            &["symbol", "sink", "kind"]
+        )
+            .unwrap()
+    });
+
+    pub static SIGNING_ERRORS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+        register_int_counter_vec!(
+            "signing_errors_total",
+            "Marks published unsigned because signing them failed",
+            &["symbol"]
+        )
+            .unwrap()
+    });
+
+    pub static PUBLISH_SKIPPED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+        register_int_counter_vec!(
+            "publish_skipped_total",
+            "Chain pushes replaced by a newer update while the symbol's previous push was in flight",
+            &["symbol", "sink"]
+        )
+            .unwrap()
+    });
+
     pub fn init() {}
 }
 
@@ -52,6 +107,7 @@ mod imp {
     // No-op stand-ins so the rest of the code doesn't need #[cfg] everywhere.
     pub struct IntCounterVec;
     pub struct HistogramVec;
+    pub struct GaugeVec;
     impl IntCounterVec {
         pub fn with_label_values(&self, _labels: &[&str]) -> &Self {
             self
@@ -64,11 +120,23 @@ mod imp {
         }
         pub fn observe(&self, _v: f64) {}
     }
+    impl GaugeVec {
+        pub fn with_label_values(&self, _labels: &[&str]) -> &Self {
+            self
+        }
+        pub fn set(&self, _v: f64) {}
+    }
 
     pub static REQUESTS_TOTAL: IntCounterVec = IntCounterVec;
     pub static RESPONSES_TOTAL: IntCounterVec = IntCounterVec;
     pub static PROVIDER_ERRORS_TOTAL: IntCounterVec = IntCounterVec;
+    pub static PROVIDER_LATE_TOTAL: IntCounterVec = IntCounterVec;
     pub static PROVIDER_LATENCY_SECONDS: HistogramVec = HistogramVec;
+    pub static PROVIDER_SCORE: GaugeVec = GaugeVec;
+    pub static PROVIDER_QUARANTINE_TOTAL: IntCounterVec = IntCounterVec;
+    pub static PUBLISH_SUPPRESSED_TOTAL: IntCounterVec = IntCounterVec;
+    pub static PUBLISH_SKIPPED_TOTAL: IntCounterVec = IntCounterVec;
+    pub static SIGNING_ERRORS_TOTAL: IntCounterVec = IntCounterVec;
 
     pub fn init() {}
 }
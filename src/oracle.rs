@@ -1,48 +1,27 @@
 // src/oracle.rs
-use chrono::Utc;
-use futures::future::join_all;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
-use crate::config::OracleConfig;
-use crate::funding::{Ema, FundingEngine};
+use crate::audit::{
+    BreakerEvent, CmeReport, ConsensusReport, FilterParams, FundingReport, PremiumReport, ProviderFailure, PublishedMark, QuoteRecord,
+    QuoteVerdict, TickReport,
+};
+use crate::clock::{self, SharedClock};
+use crate::config::{ms, FundingConfig, OracleConfig, PerpVenueConfig, ReputationConfig, StateConfig};
+use crate::funding::{Ema, FundingAccumulator, FundingEngine};
+use crate::health::{HealthReason, HealthTracker, TickOutcome};
 use crate::index::cfd_consensus::CfdConsensus;
-use crate::providers::CfdProvider;
+use crate::index::cmf::CmfIndexBuilder;
+use crate::index::IndexBuilder;
+use crate::price_feed::PriceFeedAggregator;
+use crate::metrics;
+use crate::premium::PremiumIndex;
+use crate::providers::{CfdProvider, CmeProvider, PerpVenue, ProviderOpts};
 use crate::publishing::Publisher;
-use crate::types::{CfdQuote, IndexTick};
-
-#[derive(Debug, Clone)]
-struct CircuitBreaker {
-    per_min_threshold: f64,
-    last_anchor_price: Option<f64>,
-    last_anchor_ms: Option<i64>,
-}
-impl CircuitBreaker {
-    fn new(per_min_threshold: f64) -> Self {
-        Self { per_min_threshold, last_anchor_price: None, last_anchor_ms: None }
-    }
-    fn tripped(&mut self, px: f64, ts_ms: i64) -> bool {
-        match (self.last_anchor_price, self.last_anchor_ms) {
-            (Some(base_px), Some(base_ts)) => {
-                let dt_ms = (ts_ms - base_ts).max(1) as f64;
-                let dt_ratio = 60_000.0 / dt_ms; // normalize to per-minute
-                let change = (px / base_px - 1.0).abs() * dt_ratio;
-                if change > self.per_min_threshold {
-                    return true;
-                }
-                if dt_ms >= 10_000.0 {
-                    self.last_anchor_price = Some(px);
-                    self.last_anchor_ms = Some(ts_ms);
-                }
-                false
-            }
-            _ => {
-                self.last_anchor_price = Some(px);
-                self.last_anchor_ms = Some(ts_ms);
-                false
-            }
-        }
-    }
-}
+use crate::reputation::{ReputationEventKind, ReputationTracker};
+use crate::risk::{BreakerConfig, RiskEngine};
+use crate::state::{OracleState, SavedMark};
+use crate::types::{CfdQuote, CmfInputs, FundingKind, FuturesLeg, ImpactPrices, IndexTick};
 
 pub struct Oracle<Pu>
 where
@@ -51,11 +30,23 @@ where
     pub cfg: OracleConfig,
     pub publisher: Pu,
     pub cfds: Vec<Arc<dyn CfdProvider + Send + Sync>>,
+    pub cme: Option<Arc<dyn CmeProvider + Send + Sync>>,
+    pub venue: Option<(Arc<dyn PerpVenue + Send + Sync>, PerpVenueConfig)>,
+    pub premium: Option<PremiumIndex>, // Some iff `venue` is
     pub name: String,
     pub last_good_mark: Option<IndexTick>,
     pub funding_ref_ema: Ema,
     pub funding_engine: FundingEngine,
-    cb: CircuitBreaker,
+    pub funding_acc: FundingAccumulator,
+    pub funding_last_rate: Option<f64>, // last settled rate; bounds the next one
+    pub price_feed: PriceFeedAggregator,
+    pub clock: SharedClock,
+    pub health: HealthTracker,
+    pub provider_opts: HashMap<String, ProviderOpts>, // by `CfdProvider::name()`; missing = defaults
+    pub reputation: ReputationTracker,
+    pub risk: RiskEngine,
+    late: Arc<Mutex<Vec<CfdQuote>>>,
+    in_flight: Arc<Mutex<HashSet<&'static str>>>, // providers whose request outlived its tick
 }
 
 impl<Pu> Oracle<Pu>
@@ -69,34 +60,200 @@ where
         funding_engine: FundingEngine,
     ) -> Self {
         Self {
-            cb: CircuitBreaker::new(0.07),
-            funding_ref_ema: Ema::new(0.005),
+            risk: RiskEngine::new(BreakerConfig::from(&cfg)),
+            funding_ref_ema: Ema::new(funding_engine.ema_alpha),
             cfg,
             publisher,
             cfds,
+            cme: None,
+            venue: None,
+            premium: None,
             name: String::new(),
             last_good_mark: None,
+            funding_acc: FundingAccumulator::new(funding_engine.interval_sec),
+            funding_last_rate: None,
             funding_engine,
+            price_feed: PriceFeedAggregator::new(3600),
+            clock: clock::system(),
+            health: HealthTracker::default(),
+            provider_opts: HashMap::new(),
+            reputation: ReputationTracker::new(ReputationConfig::default()),
+            late: Arc::default(),
+            in_flight: Arc::default(),
+        }
+    }
+
+    pub fn with_provider_opts(mut self, opts: HashMap<String, ProviderOpts>) -> Self {
+        self.provider_opts = opts;
+        self
+    }
+
+    /// Provider scoring and quarantine settings (defaults otherwise).
+    pub fn with_reputation(mut self, cfg: ReputationConfig) -> Self {
+        self.reputation = ReputationTracker::new(cfg);
+        self
+    }
+
+    /// Futures source for hybrid marks; used unless `cfg.mode_cfd_only`. Its
+    /// timeout comes from `provider_opts` under its name.
+    pub fn with_cme(mut self, cme: Arc<dyn CmeProvider + Send + Sync>) -> Self {
+        self.cme = Some(cme);
+        self
+    }
+
+    /// Perp venue whose impact prices give the premium index funding is based on;
+    /// without one, funding measures the mark against the reference EMA. Its
+    /// timeout comes from `provider_opts` under its name.
+    pub fn with_venue(mut self, venue: Arc<dyn PerpVenue + Send + Sync>, cfg: PerpVenueConfig) -> Self {
+        self.premium = Some(PremiumIndex::new(&cfg));
+        self.venue = Some((venue, cfg));
+        self
+    }
+
+    /// Run on `clock` instead of the wall clock (replays, tests).
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.risk = RiskEngine::new(self.risk.breaker.cfg.clone()).with_clock(clock.clone());
+        self.clock = clock;
+        self
+    }
+
+    /// Swap in new parameters, keeping `last_good_mark`, the breaker's history and
+    /// trip state, and the funding EMA. The symbol and expo identify the feed and can't change here.
+    pub fn apply_config(&mut self, cfg: OracleConfig) -> anyhow::Result<()> {
+        if cfg.symbol != self.cfg.symbol || cfg.expo != self.cfg.expo {
+            anyhow::bail!("symbol/expo can't change without a restart");
+        }
+        self.risk.breaker.cfg = BreakerConfig::from(&cfg);
+        self.cfg = cfg;
+        Ok(())
+    }
+
+    /// Update funding parameters in place; the reference EMA and the last settled
+    /// rate keep their values. A new `interval_sec` drops the interval accumulated so far.
+    pub fn apply_funding(&mut self, f: &FundingConfig) {
+        if f.interval_sec != self.funding_acc.interval_sec {
+            tracing::warn!("{}: funding interval changed; restarting accumulation", self.cfg.symbol);
+            self.funding_acc = FundingAccumulator::new(f.interval_sec);
         }
+        self.funding_engine = FundingEngine::from_config(f);
+        self.funding_ref_ema.alpha = f.ema_alpha;
     }
 
-    pub async fn tick_once(&mut self) {
+    /// Runtime state worth keeping across a restart (see `state`).
+    pub fn snapshot(&self) -> OracleState {
+        OracleState {
+            saved_ms: self.clock.now_ms(),
+            last_good_mark: self.last_good_mark.as_ref().map(SavedMark::from),
+            breaker: self.risk.breaker.state(),
+            funding_ref_ema: self.funding_ref_ema.value,
+            price_feed: self.price_feed.state(),
+            reputation: self.reputation.scores().to_vec(),
+            premium: self.premium.as_ref().map(PremiumIndex::samples).unwrap_or_default(),
+            funding_acc: self.funding_acc.state(),
+            funding_last_rate: self.funding_last_rate,
+        }
+    }
+
+    /// Put back a snapshot from a previous run, dropping whatever is older than
+    /// `cfg` allows. Returns the parts that were restored.
+    pub fn restore(&mut self, state: OracleState, cfg: &StateConfig) -> Vec<&'static str> {
+        let now = self.clock.now_ms();
+        let age = now - state.saved_ms;
+        let mut parts = Vec::new();
+        if age <= cfg.reputation_max_age_ms as i64 {
+            self.reputation.restore(state.reputation);
+            parts.push("provider scores");
+        }
+        if age > cfg.max_age_ms as i64 {
+            return parts;
+        }
+        if let Some(mark) = state.last_good_mark.filter(|m| now - m.ts_ms <= cfg.max_age_ms as i64) {
+            self.last_good_mark = Some(mark.to_tick(&self.cfg.symbol));
+            parts.push("last good mark");
+        }
+        self.risk.breaker.restore(state.breaker);
+        parts.push("breaker");
+        if state.funding_ref_ema.is_some() {
+            self.funding_ref_ema.value = state.funding_ref_ema;
+            parts.push("funding EMA");
+        }
+        if state.funding_acc.last.is_some() {
+            self.funding_acc.restore(state.funding_acc);
+            parts.push("funding interval");
+        }
+        if state.funding_last_rate.is_some() {
+            self.funding_last_rate = state.funding_last_rate;
+            parts.push("last funding rate");
+        }
+        if state.price_feed.last_ts_ms.is_some() {
+            self.price_feed.restore(state.price_feed);
+            parts.push("price feed EMA");
+        }
+        if let Some(premium) = self.premium.as_mut().filter(|_| !state.premium.is_empty()) {
+            premium.restore(state.premium);
+            parts.push("premium samples");
+        }
+        parts
+    }
+
+    /// One pass of the pipeline. Every exit goes through `finish`, which publishes
+    /// the resulting health so consumers can tell a fresh mark from a frozen one.
+    /// The returned report records every quote, filter and publish along the way.
+    pub async fn tick_once(&mut self) -> TickReport {
+        let now = self.clock.now_ms();
+        let mut report = TickReport::new(&self.cfg.symbol, now, FilterParams {
+            max_staleness_ms: self.derived_staleness_ms(),
+            min_fresh: self.cfg.cfd_min_fresh.max(1),
+            mad_k: self.cfg.cfd_mad_k,
+            dispersion_bps_max: self.cfg.cfd_dispersion_bps_max,
+            max_step_per_tick: self.cfg.max_step_per_tick.max(0.0005),
+        });
         if !self.hours_ok() {
-            return;
+            return self.finish(report, TickOutcome::Halted, vec![HealthReason::HoursClosed], now).await;
+        }
+
+        let late = self.take_late_quotes();
+        if !late.is_empty() {
+            tracing::info!("{}: recording {} late quote(s)", self.cfg.symbol, late.len());
+            report.quotes.extend(late.iter().map(|q| QuoteRecord::new(q, now, QuoteVerdict::Late, 0.0)));
+            let r = self.publisher.publish_quotes(&self.cfg.symbol, &late).await;
+            report.record_publish("quotes", r);
         }
 
-        let (quotes, _attempted) = self.collect_cfd_quotes().await;
+        let start = tokio::time::Instant::now();
+        let ((quotes, attempted), legs, impact) = tokio::join!(
+            self.collect_cfd_quotes(start, &mut report),
+            self.fetch_cme_legs(start),
+            self.fetch_impact_prices(start, now)
+        );
+        if !quotes.is_empty() {
+            let r = self.publisher.publish_quotes(&self.cfg.symbol, &quotes).await;
+            report.record_publish("quotes", r);
+        }
 
         // Staleness gate (~3×tau by default)
-        let now = Utc::now().timestamp_millis();
-        let max_stale_ms = self.derived_staleness_ms();
-        let fresh: Vec<CfdQuote> = quotes
-            .into_iter()
-            .filter(|q| (now - q.ts_ms).unsigned_abs() <= max_stale_ms)
-            .collect();
+        let now = self.clock.now_ms();
+        let max_stale_ms = report.filters.max_staleness_ms;
+        let (fresh, stale): (Vec<CfdQuote>, Vec<CfdQuote>) =
+            quotes.into_iter().partition(|q| (now - q.ts_ms).unsigned_abs() <= max_stale_ms);
+        report.quotes.extend(stale.iter().map(|q| QuoteRecord::new(q, now, QuoteVerdict::Stale, 0.0)));
 
-        if fresh.len() < self.cfg.cfd_min_fresh.max(1) {
-            return;
+        // Quarantined providers are scored against the consensus but don't vote in it
+        let (benched, fresh): (Vec<CfdQuote>, Vec<CfdQuote>) =
+            fresh.into_iter().partition(|q| self.reputation.is_quarantined(q.src.name()));
+        report.quotes.extend(benched.iter().map(|q| QuoteRecord::new(q, now, QuoteVerdict::Quarantined, 0.0)));
+
+        // Not enough data: withhold the price rather than repeat an old one.
+        if fresh.len() < report.filters.min_fresh {
+            report.quotes.extend(fresh.iter().map(|q| QuoteRecord::new(q, now, QuoteVerdict::Unused, 0.0)));
+            return self.finish(report, TickOutcome::Stale, vec![HealthReason::NotEnoughFresh], now).await;
+        }
+        let mut reasons = Vec::new();
+        if fresh.len() + benched.len() < attempted {
+            reasons.push(HealthReason::ProvidersMissing);
+        }
+        if !benched.is_empty() {
+            reasons.push(HealthReason::ProvidersQuarantined);
         }
 
         // Robust consensus (4-arg constructor)
@@ -105,58 +262,159 @@ where
             self.cfg.expo,
             self.cfg.cfd_tau_ms,
             self.cfg.cfd_mad_k,
-        );
+        )
+        .with_clock(self.clock.clone())
+        .with_weights(self.provider_opts.iter().map(|(k, o)| (k.clone(), o.weight)).collect());
 
-        let (mut mark, stats) = match builder.build(&fresh) {
+        let (mut mark, stats, detail) = match builder.build_detailed(&fresh) {
             Ok(x) => x,
-            Err(_) => return,
+            Err(_) => {
+                report.quotes.extend(fresh.iter().map(|q| QuoteRecord::new(q, now, QuoteVerdict::Unused, 0.0)));
+                return self.finish(report, TickOutcome::Stale, vec![HealthReason::ConsensusFailed], now).await;
+            }
         };
+        for (i, q) in fresh.iter().enumerate() {
+            let verdict = if detail.kept[i] { QuoteVerdict::Kept } else { QuoteVerdict::Outlier };
+            report.quotes.push(QuoteRecord::new(q, now, verdict, detail.weights[i]));
+        }
+        report.consensus = Some(ConsensusReport { median: detail.median, mad: detail.mad, band: detail.band, stats });
 
-        // Optional dispersion check (soft)
-        let _too_wide = stats.spread_bps > self.cfg.cfd_dispersion_bps_max;
+        // Dispersion check (soft: publish, but flag as degraded)
+        if stats.spread_bps > self.cfg.cfd_dispersion_bps_max {
+            reasons.push(HealthReason::WideDispersion);
+        }
+
+        // Hybrid: blend in the CMF index when the futures legs are fresh, else stay CFD-only
+        if let Some(legs) = legs {
+            report.cme = Some(self.blend_cmf(&mut mark, legs, now));
+        }
 
         // Per-tick step clamp vs last good mark
+        report.pre_clamp_price = Some(mark.price);
         if let Some(prev) = &self.last_good_mark {
-            let step = self.cfg.max_step_per_tick.max(0.0005);
+            let step = report.filters.max_step_per_tick;
             let lo = prev.price * (1.0 - step);
             let hi = prev.price * (1.0 + step);
-            if mark.price < lo {
-                mark.price = lo;
-            } else if mark.price > hi {
-                mark.price = hi;
+            report.clamp_bounds = Some((lo, hi));
+            if mark.price < lo || mark.price > hi {
+                mark.price = mark.price.clamp(lo, hi);
+                reasons.push(HealthReason::StepClamped);
             }
         }
+        report.post_clamp_price = Some(mark.price);
 
-        // Circuit breaker
-        if self.cb.tripped(mark.price, mark.ts_ms) {
-            if let Some(good) = &self.last_good_mark {
-                mark = good.clone();
-            } else {
-                return;
+        // Circuit breaker: hold the last good mark (frozen), or nothing if there is none
+        let breaker = self.risk.eval_circuit_breaker(mark.price, mark.ts_ms);
+        match breaker.event {
+            Some(BreakerEvent::Tripped) => tracing::warn!("{}: circuit breaker tripped at {}", self.cfg.symbol, mark.price),
+            Some(BreakerEvent::Reset) => tracing::info!("{}: circuit breaker reset at {}", self.cfg.symbol, mark.price),
+            None => {}
+        }
+        let tripped = breaker.tripped;
+        report.breaker = Some(breaker);
+        if tripped {
+            let mut reasons = vec![HealthReason::BreakerTripped];
+            if !self.publish_frozen(&mut report).await {
+                reasons.push(HealthReason::NoPriorMark);
             }
-        } else {
-            self.last_good_mark = Some(mark.clone());
+            return self.finish(report, TickOutcome::Halted, reasons, now).await;
         }
+        self.last_good_mark = Some(mark.clone());
 
         // Publish mark
-        if let Err(e) = self.publisher.publish_index(mark.clone()).await {
-            tracing::warn!("publish_index failed: {e:?}");
+        let r = self.publisher.publish_index(mark.clone()).await;
+        report.record_publish("index", r);
+        report.mark = Some(PublishedMark { price: mark.price, ts_ms: mark.ts_ms, frozen: false });
+        let r = self.publisher.publish_consensus(&mark.symbol, stats).await;
+        report.record_publish("consensus", r);
+        match self.price_feed.update(&mark, &stats) {
+            Ok(feed) => {
+                let r = self.publisher.publish_price_feed(feed).await;
+                report.record_publish("price_feed", r);
+            }
+            Err(e) => tracing::warn!("price feed for {} skipped: {e}", mark.symbol),
         }
 
-        // Funding vs slow EMA reference
+        // Funding basis: the perp premium index, or the mark vs its slow EMA without one.
+        // It accumulates over the funding interval; crossing a boundary settles the
+        // interval's time-weighted average, and every tick publishes the prediction
+        // for the next settlement.
         let ref_px = self.funding_ref_ema.update(mark.price);
-        let ref_tick = IndexTick {
-            symbol: mark.symbol.clone(),
-            price: ref_px,
-            expo: mark.expo,
-            ts_ms: mark.ts_ms,
-            source: "ref-ema",
-            window_sec: 0,
+        if let Some(impact) = impact {
+            report.premium = Some(self.sample_premium(impact, mark.price, now));
+        }
+        let (basis, source) = match report.premium.as_ref().and_then(|p| p.index) {
+            Some(premium) => (premium, "premium"),
+            None => ((mark.price - ref_px) / ref_px, "ref-ema"),
         };
-        let funding = self.funding_engine.compute(&mark, &ref_tick);
-        if let Err(e) = self.publisher.publish_funding(funding).await {
-            tracing::warn!("publish_funding failed: {e:?}");
+        let settlement = self.funding_acc.observe(basis, now);
+        let previous_rate = self.funding_last_rate;
+        let mut settled_rate = None;
+        if let Some(s) = settlement {
+            let fu = self.funding_engine.update(&mark.symbol, s.twap, previous_rate, FundingKind::Settled, s.settle_ms, now);
+            tracing::info!("{}: funding settled at {} ({} over {}ms)", self.cfg.symbol, s.settle_ms, fu.rate, s.covered_ms);
+            settled_rate = Some(fu.rate);
+            self.funding_last_rate = settled_rate;
+            let r = self.publisher.publish_funding(fu).await;
+            report.record_publish("funding_settled", r);
+        }
+        let twap = self.funding_acc.twap(now).unwrap_or(basis);
+        let next_settle_ms = self.funding_acc.next_settle_ms(now);
+        let predicted = self.funding_engine.update(&mark.symbol, twap, self.funding_last_rate, FundingKind::Predicted, next_settle_ms, now);
+        report.funding = Some(FundingReport {
+            basis,
+            source: source.to_string(),
+            twap,
+            predicted_rate: predicted.rate,
+            next_settle_ms,
+            settlement,
+            settled_rate,
+            previous_rate,
+        });
+        let r = self.publisher.publish_funding(predicted).await;
+        report.record_publish("funding", r);
+
+        self.finish(report, TickOutcome::Marked, reasons, now).await
+    }
+
+    /// Republish the last good mark unchanged (its `ts_ms` stays old). Funding and
+    /// the price-feed EMA are not fed frozen prices. `false` if there is none.
+    async fn publish_frozen(&self, report: &mut TickReport) -> bool {
+        let Some(good) = self.last_good_mark.clone() else { return false };
+        report.mark = Some(PublishedMark { price: good.price, ts_ms: good.ts_ms, frozen: true });
+        let r = self.publisher.publish_index(good).await;
+        report.record_publish("index", r);
+        true
+    }
+
+    async fn finish(&mut self, mut report: TickReport, outcome: TickOutcome, reasons: Vec<HealthReason>, now: i64) -> TickReport {
+        self.update_reputation(&mut report, now);
+        let health = self.health.observe(&self.cfg.symbol, outcome, reasons, now);
+        let r = self.publisher.publish_health(health.clone()).await;
+        report.record_publish("health", r);
+        report.health = health;
+        report.suppressed = self.publisher.take_suppressed(&self.cfg.symbol);
+        report
+    }
+
+    /// Score the providers on what this tick saw and apply any quarantine changes.
+    fn update_reputation(&mut self, report: &mut TickReport, now: i64) {
+        self.reputation.observe_report(report);
+        let events = self.reputation.evaluate(now, self.cfg.cfd_min_fresh.max(1));
+        let symbol = self.cfg.symbol.as_str();
+        for e in &events {
+            let kind = match e.kind {
+                ReputationEventKind::Quarantined => "quarantined",
+                ReputationEventKind::Reinstated => "reinstated",
+            };
+            tracing::warn!("{symbol}: provider {} {kind} (score {:.3})", e.provider, e.score);
+            metrics::PROVIDER_QUARANTINE_TOTAL.with_label_values(&[symbol, &e.provider, kind]).inc();
+        }
+        for p in self.reputation.scores() {
+            metrics::PROVIDER_SCORE.with_label_values(&[symbol, &p.provider]).set(p.score);
         }
+        report.reputation = self.reputation.scores().to_vec();
+        report.reputation_events = events;
     }
 
     fn derived_staleness_ms(&self) -> u64 {
@@ -172,35 +430,557 @@ where
         }
     }
 
-    async fn collect_cfd_quotes(&self) -> (Vec<CfdQuote>, usize) {
-        let now = Utc::now().timestamp_millis();
+    /// Poll every enabled provider concurrently. Each gets until its own timeout or
+    /// the tick deadline, whichever is first. A provider that misses it keeps
+    /// running in the background until one tick interval after the tick started;
+    /// a quote arriving by then lands in `late` and is recorded on the next tick,
+    /// otherwise the request is aborted. Until then the provider is not asked
+    /// again, so a hung provider has at most one request open.
+    /// Invalid quotes and failed providers are recorded in `report`.
+    async fn collect_cfd_quotes(&self, start: tokio::time::Instant, report: &mut TickReport) -> (Vec<CfdQuote>, usize) {
+        let tick_deadline = self.tick_deadline(start);
 
-        let futs = self
-            .cfds
-            .iter()
-            .map(|prov| async move { prov.latest(&self.cfg.symbol).await });
+        let late_until = (start + ms(self.cfg.tick_interval_ms.max(1))).max(tick_deadline);
 
-        let results = join_all(futs).await;
-        let attempted = results.len();
-        let mut out = Vec::with_capacity(attempted);
+        let mut pending = Vec::with_capacity(self.cfds.len());
+        let mut busy = 0;
+        for prov in &self.cfds {
+            let opts = self.provider_opts(prov.name());
+            if !opts.enabled {
+                continue;
+            }
+            if self.in_flight.lock().unwrap().contains(prov.name()) {
+                metrics::PROVIDER_ERRORS_TOTAL.with_label_values(&[prov.name(), "in_flight"]).inc();
+                report.failures.push(ProviderFailure { provider: prov.name().to_string(), error: "previous request still in flight".into() });
+                busy += 1;
+                continue;
+            }
+            let until = opts.timeout.map_or(tick_deadline, |t| (start + t).min(tick_deadline));
+            let (p, symbol) = (prov.clone(), self.cfg.symbol.clone());
+            let handle = tokio::spawn(async move { p.latest(&symbol).await });
+            pending.push((prov.name(), until, handle));
+        }
+        let attempted = pending.len() + busy;
 
-        for res in results {
-            match res {
-                Ok(mut q) => {
-                    if !q.price.is_finite() || q.price <= 0.0 {
-                        continue;
+        let mut out = Vec::with_capacity(pending.len());
+        for (name, until, mut handle) in pending {
+            match tokio::time::timeout_at(until, &mut handle).await {
+                Ok(Ok(Ok(q))) => {
+                    metrics::PROVIDER_LATENCY_SECONDS.with_label_values(&[name]).observe(start.elapsed().as_secs_f64());
+                    match self.sanitize(q.clone()) {
+                        Some(q) => out.push(q),
+                        None => report.quotes.push(QuoteRecord::new(&q, self.clock.now_ms(), QuoteVerdict::Invalid, 0.0)),
                     }
-                    // clamp far-future timestamps
-                    if (q.ts_ms - now) > 2_000 {
-                        q.ts_ms = now;
-                    }
-                    out.push(q);
                 }
-                Err(err) => {
-                    tracing::debug!("CFD provider error: {:?}", err);
+                Ok(Ok(Err(err))) => {
+                    metrics::PROVIDER_ERRORS_TOTAL.with_label_values(&[name, "error"]).inc();
+                    tracing::debug!("CFD provider {name} error: {:?}", err);
+                    report.failures.push(ProviderFailure { provider: name.to_string(), error: format!("{err:#}") });
+                }
+                Ok(Err(join_err)) => {
+                    metrics::PROVIDER_ERRORS_TOTAL.with_label_values(&[name, "panic"]).inc();
+                    tracing::warn!("CFD provider {name} task failed: {join_err}");
+                    report.failures.push(ProviderFailure { provider: name.to_string(), error: join_err.to_string() });
+                }
+                Err(_) => {
+                    metrics::PROVIDER_LATE_TOTAL.with_label_values(&[name]).inc();
+                    tracing::debug!("CFD provider {name} missed the deadline; recording its quote when it arrives");
+                    report.failures.push(ProviderFailure { provider: name.to_string(), error: "timeout".into() });
+                    self.in_flight.lock().unwrap().insert(name);
+                    let (late, in_flight) = (self.late.clone(), self.in_flight.clone());
+                    tokio::spawn(async move {
+                        match tokio::time::timeout_at(late_until, &mut handle).await {
+                            Ok(Ok(Ok(q))) => late.lock().unwrap().push(q),
+                            Ok(_) => {}
+                            Err(_) => {
+                                tracing::debug!("CFD provider {name} still hasn't answered; aborting the request");
+                                handle.abort();
+                            }
+                        }
+                        in_flight.lock().unwrap().remove(name);
+                    });
                 }
             }
         }
         (out, attempted)
     }
+
+    /// Front/next legs from the CME provider, within the same deadlines as the
+    /// CFD quotes. `None` when the market runs CFD-only.
+    async fn fetch_cme_legs(&self, start: tokio::time::Instant) -> Option<anyhow::Result<(FuturesLeg, FuturesLeg)>> {
+        let cme = self.cme.as_ref().filter(|_| !self.cfg.mode_cfd_only)?;
+        let tick_deadline = self.tick_deadline(start);
+        let until = self.provider_opts(cme.name()).timeout.map_or(tick_deadline, |t| (start + t).min(tick_deadline));
+        Some(match tokio::time::timeout_at(until, cme.latest_f1_f2(&self.cfg.symbol)).await {
+            Ok(legs) => legs,
+            Err(_) => Err(anyhow::anyhow!("timeout")),
+        })
+    }
+
+    /// Replace `mark.price` with `cme_weight·cmf + (1-cme_weight)·cfd` and tag it
+    /// "hybrid" when both legs are fresh and the CMF builds; otherwise leave the
+    /// CFD mark alone and say why.
+    fn blend_cmf(&self, mark: &mut IndexTick, legs: anyhow::Result<(FuturesLeg, FuturesLeg)>, now: i64) -> CmeReport {
+        let w = self.cfg.cme_weight;
+        let mut r = CmeReport { cfd_price: mark.price, cmf_price: None, weight: w, used: false, fallback: None };
+        let max_age = self.cfg.cme_max_staleness_ms;
+        let cmf = legs.and_then(|(f1, f2)| {
+            if [f1, f2].iter().any(|l| (now - l.ts_ms).unsigned_abs() > max_age) {
+                anyhow::bail!("futures legs older than {max_age}ms");
+            }
+            let inputs = CmfInputs { f1, f2, target_days: self.cfg.cmf_target_days };
+            Ok(CmfIndexBuilder::new(self.cfg.symbol.clone(), self.cfg.expo).with_clock(self.clock.clone()).build(inputs)?)
+        });
+        match cmf {
+            Ok(t) if t.price.is_finite() && t.price > 0.0 => {
+                r.cmf_price = Some(t.price);
+                r.used = true;
+                mark.price = w * t.price + (1.0 - w) * mark.price;
+                mark.source = "hybrid";
+            }
+            Ok(t) => r.fallback = Some(format!("invalid cmf price {}", t.price)),
+            Err(e) => r.fallback = Some(format!("{e:#}")),
+        }
+        if let Some(why) = &r.fallback {
+            tracing::debug!("{}: CFD-only this tick ({why})", self.cfg.symbol);
+        }
+        r
+    }
+
+    /// Impact prices from the perp venue, within the same deadlines as the CFD
+    /// quotes. `None` without a venue; skipped (`Ok(None)`) until the next premium sample is due.
+    async fn fetch_impact_prices(&self, start: tokio::time::Instant, now: i64) -> Option<anyhow::Result<Option<ImpactPrices>>> {
+        let (venue, cfg) = self.venue.as_ref()?;
+        if !self.premium.as_ref().is_some_and(|p| p.due(now)) {
+            return Some(Ok(None));
+        }
+        let tick_deadline = self.tick_deadline(start);
+        let until = self.provider_opts(venue.name()).timeout.map_or(tick_deadline, |t| (start + t).min(tick_deadline));
+        Some(match tokio::time::timeout_at(until, venue.impact_prices(&self.cfg.symbol, cfg.impact_notional)).await {
+            Ok(r) => r.map(Some),
+            Err(_) => Err(anyhow::anyhow!("timeout")),
+        })
+    }
+
+    /// Sample the premium of `impact` over the mark when it is fresh and sane. The
+    /// report's `index` is what funding uses, if any samples are in the window.
+    fn sample_premium(&mut self, impact: anyhow::Result<Option<ImpactPrices>>, index: f64, now: i64) -> PremiumReport {
+        let max_age = self.venue.as_ref().map_or(0, |(_, c)| c.max_staleness_ms);
+        let premium = self.premium.as_mut().expect("premium index without a venue");
+        let mut r = PremiumReport { impact: None, sample: None, index: None, samples: 0, error: None };
+        match impact {
+            Ok(Some(ip)) => {
+                r.impact = Some(ip);
+                if (now - ip.ts_ms).unsigned_abs() > max_age {
+                    r.error = Some(format!("book older than {max_age}ms"));
+                } else if !(ip.impact_bid.is_finite() && ip.impact_ask.is_finite() && 0.0 < ip.impact_bid && ip.impact_bid <= ip.impact_ask) {
+                    r.error = Some(format!("invalid impact prices {}/{}", ip.impact_bid, ip.impact_ask));
+                } else {
+                    r.sample = Some(premium.sample(&ip, index, now));
+                }
+            }
+            Ok(None) => {} // not due yet
+            Err(e) => {
+                if let Some((venue, _)) = &self.venue {
+                    metrics::PROVIDER_ERRORS_TOTAL.with_label_values(&[venue.name(), "error"]).inc();
+                }
+                r.error = Some(format!("{e:#}"));
+            }
+        }
+        r.index = premium.value(now);
+        r.samples = premium.count(now);
+        if let Some(why) = &r.error {
+            tracing::debug!("{}: no premium sample this tick ({why})", self.cfg.symbol);
+        }
+        r
+    }
+
+    fn tick_deadline(&self, start: tokio::time::Instant) -> tokio::time::Instant {
+        start + ms(self.cfg.tick_deadline_ms.unwrap_or(self.cfg.tick_interval_ms).max(1))
+    }
+
+    fn sanitize(&self, mut q: CfdQuote) -> Option<CfdQuote> {
+        if !q.price.is_finite() || q.price <= 0.0 {
+            return None;
+        }
+        // clamp far-future timestamps
+        let now = self.clock.now_ms();
+        if (q.ts_ms - now) > 2_000 {
+            q.ts_ms = now;
+        }
+        Some(q)
+    }
+
+    fn provider_opts(&self, name: &str) -> ProviderOpts {
+        self.provider_opts.get(name).copied().unwrap_or_default()
+    }
+
+    /// Quotes that arrived after their deadline since the last call.
+    fn take_late_quotes(&self) -> Vec<CfdQuote> {
+        std::mem::take(&mut *self.late.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+    use crate::health::{HealthStatus, OracleHealth};
+    use crate::publishing::StdoutPublisher;
+    use crate::types::{CfdSource, FundingUpdate};
+    use std::sync::Mutex;
+
+    /// Quotes `price` stamped `lag_ms` behind the shared clock; `None` errors.
+    pub(crate) struct FixedCfd {
+        pub name: &'static str,
+        pub price: Mutex<Option<f64>>,
+        pub lag_ms: i64,
+        pub delay_ms: u64,
+        pub clock: ManualClock,
+    }
+
+    #[async_trait::async_trait]
+    impl CfdProvider for FixedCfd {
+        async fn latest(&self, _symbol: &str) -> anyhow::Result<CfdQuote> {
+            let price = self.price.lock().unwrap().ok_or_else(|| anyhow::anyhow!("down"))?;
+            tokio::time::sleep(std::time::Duration::from_millis(self.delay_ms)).await;
+            Ok(CfdQuote { src: CfdSource::Other(self.name.into()), price, ts_ms: self.clock.now_ms() - self.lag_ms })
+        }
+        fn name(&self) -> &'static str { self.name }
+    }
+
+    #[derive(Default)]
+    pub(crate) struct Recorder {
+        pub marks: Mutex<Vec<IndexTick>>,
+        pub health: Mutex<Vec<OracleHealth>>,
+        pub quotes: Mutex<Vec<CfdQuote>>,
+        pub funding: Mutex<Vec<FundingUpdate>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Publisher for Recorder {
+        async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+            self.marks.lock().unwrap().push(tick);
+            Ok(())
+        }
+        async fn publish_funding(&self, fu: FundingUpdate) -> anyhow::Result<()> {
+            self.funding.lock().unwrap().push(fu);
+            Ok(())
+        }
+        async fn publish_health(&self, health: OracleHealth) -> anyhow::Result<()> {
+            self.health.lock().unwrap().push(health);
+            Ok(())
+        }
+        async fn publish_quotes(&self, _symbol: &str, quotes: &[CfdQuote]) -> anyhow::Result<()> {
+            self.quotes.lock().unwrap().extend_from_slice(quotes);
+            Ok(())
+        }
+    }
+
+    pub(crate) fn provider(name: &'static str, price: f64, clock: &ManualClock) -> Arc<FixedCfd> {
+        Arc::new(FixedCfd { name, price: Mutex::new(Some(price)), lag_ms: 0, delay_ms: 0, clock: clock.clone() })
+    }
+
+    /// Both legs at `price` (`None` errors), stamped `lag_ms` behind the clock.
+    struct FixedCme {
+        price: Mutex<Option<f64>>,
+        lag_ms: Mutex<i64>,
+        clock: ManualClock,
+    }
+
+    #[async_trait::async_trait]
+    impl CmeProvider for FixedCme {
+        async fn latest_f1_f2(&self, _symbol: &str) -> anyhow::Result<(FuturesLeg, FuturesLeg)> {
+            const DAY: i64 = 86_400_000;
+            let price = self.price.lock().unwrap().ok_or_else(|| anyhow::anyhow!("closed"))?;
+            let (now, ts) = (self.clock.now_ms(), self.clock.now_ms() - *self.lag_ms.lock().unwrap());
+            Ok((
+                FuturesLeg { price, ts_ms: ts, expiry_ts_ms: now + 10 * DAY },
+                FuturesLeg { price, ts_ms: ts, expiry_ts_ms: now + 40 * DAY },
+            ))
+        }
+        fn name(&self) -> &'static str { "cme" }
+    }
+
+    #[tokio::test]
+    async fn hybrid_blends_fresh_cmf_and_falls_back_to_cfd() {
+        let clock = ManualClock::new(1_700_000_000_000);
+        let cme = Arc::new(FixedCme { price: Mutex::new(Some(110.0)), lag_ms: Mutex::new(600_000), clock: clock.clone() });
+        let rec = Arc::new(Recorder::default());
+        let cfg = OracleConfig { symbol: "CORN_PERP".into(), expo: -8, cmf_target_days: 30.0, max_step_per_tick: 0.1, ..OracleConfig::default() };
+        let cfds: Vec<Arc<dyn CfdProvider + Send + Sync>> = vec![provider("a", 100.0, &clock), provider("b", 100.0, &clock)];
+        let mut oracle = Oracle::new(cfg, rec.clone(), cfds, FundingEngine::new(0.02, 0.005, 8 * 3600))
+            .with_clock(Arc::new(clock.clone()))
+            .with_cme(cme.clone());
+
+        // Legs 10 minutes old: CFD-only.
+        let r = oracle.tick_once().await;
+        assert!(!r.cme.as_ref().unwrap().used);
+        let last = |rec: &Recorder| rec.marks.lock().unwrap().last().map(|m| (m.price, m.source)).unwrap();
+        assert_eq!(last(&rec), (100.0, "cfd-consensus"));
+
+        // Fresh legs: 50/50 blend with the CMF.
+        *cme.lag_ms.lock().unwrap() = 0;
+        clock.advance_ms(600_000);
+        let r = oracle.tick_once().await;
+        assert_eq!(r.cme.as_ref().unwrap().cmf_price, Some(110.0));
+        assert_eq!(last(&rec), (105.0, "hybrid"));
+
+        // CME closed: back to CFD-only with the reason recorded.
+        *cme.price.lock().unwrap() = None;
+        clock.advance_ms(600_000);
+        let r = oracle.tick_once().await;
+        assert_eq!(r.cme.unwrap().fallback.as_deref(), Some("closed"));
+        assert_eq!(last(&rec), (100.0, "cfd-consensus"));
+
+        // mode_cfd_only skips CME entirely.
+        oracle.cfg.mode_cfd_only = true;
+        assert!(oracle.tick_once().await.cme.is_none());
+    }
+
+    #[tokio::test]
+    async fn deadlines_weights_and_enable_flags() {
+        let clock = ManualClock::new(1_700_000_000_000);
+        let slow = Arc::new(FixedCfd { delay_ms: 300, ..Arc::into_inner(provider("slow", 90.0, &clock)).unwrap() });
+        let off = provider("off", 50.0, &clock);
+        let rec = Arc::new(Recorder::default());
+        let cfg = OracleConfig { symbol: "CORN_PERP".into(), expo: -8, cfd_min_fresh: 2, tick_deadline_ms: Some(50), ..OracleConfig::default() };
+        let opts = HashMap::from([
+            ("b".to_string(), ProviderOpts { weight: 3.0, ..ProviderOpts::default() }),
+            ("off".to_string(), ProviderOpts { enabled: false, ..ProviderOpts::default() }),
+        ]);
+        let cfds: Vec<Arc<dyn CfdProvider + Send + Sync>> =
+            vec![provider("a", 100.0, &clock), provider("b", 100.4, &clock), slow, off];
+        let mut oracle = Oracle::new(cfg, rec.clone(), cfds, FundingEngine::new(0.02, 0.005, 8 * 3600))
+            .with_clock(Arc::new(clock.clone()))
+            .with_provider_opts(opts);
+
+        oracle.tick_once().await;
+        let mark = rec.marks.lock().unwrap()[0].price;
+        assert!(mark > 100.2 && mark < 100.4, "weighted towards b: {mark}");
+        let srcs = |rec: &Recorder| rec.quotes.lock().unwrap().iter().map(|q| q.src.name().to_string()).collect::<Vec<_>>();
+        assert_eq!(srcs(&rec), ["a", "b"]); // slow missed the deadline, off was never asked
+
+        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+        oracle.tick_once().await;
+        assert_eq!(srcs(&rec)[2], "slow"); // late quote recorded at the start of the next tick
+    }
+
+    #[tokio::test]
+    async fn a_hung_provider_has_one_request_open_at_a_time() {
+        let clock = ManualClock::new(1_700_000_000_000);
+        let hung = Arc::new(FixedCfd { delay_ms: 60_000, ..Arc::into_inner(provider("hung", 90.0, &clock)).unwrap() });
+        let cfg = OracleConfig { symbol: "CORN_PERP".into(), expo: -8, tick_interval_ms: 100, tick_deadline_ms: Some(50), ..OracleConfig::default() };
+        let cfds: Vec<Arc<dyn CfdProvider + Send + Sync>> = vec![provider("a", 100.0, &clock), provider("b", 100.0, &clock), hung];
+        let mut oracle = Oracle::new(cfg, StdoutPublisher {}, cfds, FundingEngine::new(0.02, 0.005, 8 * 3600))
+            .with_clock(Arc::new(clock.clone()));
+        let errors = |r: TickReport| r.failures.into_iter().map(|f| f.error).collect::<Vec<_>>();
+
+        assert_eq!(errors(oracle.tick_once().await), ["timeout"]);
+        assert_eq!(errors(oracle.tick_once().await), ["previous request still in flight"]);
+        // Past its late-quote window the request is aborted and the provider is asked again.
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(errors(oracle.tick_once().await), ["timeout"]);
+        assert!(oracle.take_late_quotes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn health_tracks_stale_and_breaker_ticks() {
+        let clock = ManualClock::new(1_700_000_000_000);
+        let (a, b) = (provider("a", 100.0, &clock), provider("b", 100.2, &clock));
+        let rec = Arc::new(Recorder::default());
+        let cfg = OracleConfig { symbol: "CORN_PERP".into(), expo: -8, circuit_breaker_per_min: 0.015, ..OracleConfig::default() };
+        let mut oracle = Oracle::new(cfg, rec.clone(), vec![a.clone(), b.clone()], FundingEngine::new(0.02, 0.005, 8 * 3600))
+            .with_clock(Arc::new(clock.clone()));
+
+        assert_eq!(oracle.tick_once().await.health.status, HealthStatus::Recovering);
+
+        // One provider down: below cfd_min_fresh, so nothing is published.
+        *b.price.lock().unwrap() = None;
+        clock.advance_ms(1_000);
+        let r = oracle.tick_once().await;
+        let h = &r.health;
+        assert_eq!((h.status, h.reasons.as_slice()), (HealthStatus::Stale, &[HealthReason::NotEnoughFresh][..]));
+        assert_eq!((r.count(QuoteVerdict::Unused), r.failures[0].provider.as_str()), (1, "b"));
+        assert_eq!(rec.marks.lock().unwrap().len(), 1);
+
+        // A 30% jump is clamped, then trips the breaker: the old mark is republished frozen.
+        *a.price.lock().unwrap() = Some(130.0);
+        *b.price.lock().unwrap() = Some(130.0);
+        clock.advance_ms(1_000);
+        let r = oracle.tick_once().await;
+        assert_eq!(r.health.status, HealthStatus::Halted);
+        assert_eq!(r.health.reasons, vec![HealthReason::BreakerTripped]);
+        assert_eq!((r.pre_clamp_price, r.post_clamp_price), (Some(130.0), r.clamp_bounds.map(|b| b.1)));
+        assert_eq!(r.breaker.as_ref().unwrap().event, Some(BreakerEvent::Tripped));
+        assert!(r.mark.as_ref().unwrap().frozen);
+        let marks = rec.marks.lock().unwrap();
+        assert_eq!(marks.len(), 2);
+        assert_eq!(marks[1].ts_ms, marks[0].ts_ms);
+        assert_eq!(rec.health.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn quarantines_a_persistent_outlier_and_reinstates_it() {
+        let clock = ManualClock::new(1_700_000_000_000);
+        let bad = provider("c", 110.0, &clock);
+        let rec = Arc::new(Recorder::default());
+        let cfg = OracleConfig { symbol: "CORN_PERP".into(), expo: -8, ..OracleConfig::default() };
+        let rep = ReputationConfig { window: 4, min_samples: 3, probation_ms: 5_000, ..ReputationConfig::default() };
+        let cfds: Vec<Arc<dyn CfdProvider + Send + Sync>> = vec![provider("a", 100.0, &clock), provider("b", 100.1, &clock), bad.clone()];
+        let mut oracle = Oracle::new(cfg, rec.clone(), cfds, FundingEngine::new(0.02, 0.005, 8 * 3600))
+            .with_clock(Arc::new(clock.clone()))
+            .with_reputation(rep);
+
+        let mut reports = Vec::new();
+        for _ in 0..4 {
+            reports.push(oracle.tick_once().await);
+            clock.advance_ms(1_000);
+        }
+        let events = &reports[2].reputation_events;
+        assert_eq!((events[0].provider.as_str(), events[0].kind), ("c", ReputationEventKind::Quarantined));
+        assert_eq!(reports[3].count(QuoteVerdict::Quarantined), 1);
+        assert!(reports[3].health.reasons.contains(&HealthReason::ProvidersQuarantined));
+
+        // Back in line: scored while benched, reinstated once probation is over.
+        *bad.price.lock().unwrap() = Some(100.05);
+        let mut back = None;
+        for _ in 0..20 {
+            let r = oracle.tick_once().await;
+            if let Some(e) = r.reputation_events.iter().find(|e| e.kind == ReputationEventKind::Reinstated) {
+                back = Some(e.ts_ms);
+                break;
+            }
+            clock.advance_ms(1_000);
+        }
+        let since = reports[2].reputation_events[0].ts_ms;
+        assert!(back.is_some_and(|t| t - since >= 5_000), "{back:?}");
+        assert_eq!(oracle.tick_once().await.count(QuoteVerdict::Kept), 3);
+    }
+
+    /// Impact bid/ask stamped with the shared clock; `None` errors.
+    struct FixedVenue {
+        impact: Mutex<Option<(f64, f64)>>,
+        clock: ManualClock,
+    }
+
+    #[async_trait::async_trait]
+    impl PerpVenue for FixedVenue {
+        async fn order_book(&self, _symbol: &str) -> anyhow::Result<crate::types::OrderBook> {
+            anyhow::bail!("impact prices only")
+        }
+        async fn impact_prices(&self, _symbol: &str, _notional: f64) -> anyhow::Result<ImpactPrices> {
+            let (impact_bid, impact_ask) = self.impact.lock().unwrap().ok_or_else(|| anyhow::anyhow!("venue down"))?;
+            Ok(ImpactPrices { impact_bid, impact_ask, ts_ms: self.clock.now_ms() })
+        }
+        fn name(&self) -> &'static str { "venue" }
+    }
+
+    #[tokio::test]
+    async fn funding_follows_the_venue_premium_and_falls_back_to_the_ema() {
+        let clock = ManualClock::new(1_700_000_000_000);
+        let venue = Arc::new(FixedVenue { impact: Mutex::new(Some((100.5, 100.7))), clock: clock.clone() });
+        let rec = Arc::new(Recorder::default());
+        let cfg = OracleConfig { symbol: "CORN_PERP".into(), expo: -8, ..OracleConfig::default() };
+        let cfds: Vec<Arc<dyn CfdProvider + Send + Sync>> = vec![provider("a", 100.0, &clock), provider("b", 100.0, &clock)];
+        let mut oracle = Oracle::new(cfg, rec.clone(), cfds, FundingEngine::new(0.02, 0.005, 8 * 3600))
+            .with_clock(Arc::new(clock.clone()))
+            .with_venue(venue.clone(), PerpVenueConfig::new("venue"));
+        let rate = |rec: &Recorder| rec.funding.lock().unwrap().last().unwrap().rate;
+
+        // The perp trades 0.5% over the index; the mark EMA alone would say 0.
+        let r = oracle.tick_once().await;
+        let p = r.premium.unwrap();
+        assert_eq!((p.sample, p.index, p.samples), (Some(0.005), Some(0.005), 1));
+        assert_eq!(r.funding.unwrap().source, "premium");
+        assert!((rate(&rec) - 0.02 * 0.005).abs() < 1e-12);
+
+        // Within `sample_ms` the venue isn't asked again; the window still counts.
+        clock.advance_ms(500);
+        let p = oracle.tick_once().await.premium.unwrap();
+        assert_eq!((p.impact, p.sample, p.index), (None, None, Some(0.005)));
+
+        // Venue down: the error is recorded, and once the window has no samples
+        // left funding falls back to the reference EMA.
+        *venue.impact.lock().unwrap() = None;
+        clock.advance_ms(30_000);
+        let p = oracle.tick_once().await.premium.unwrap();
+        assert_eq!((p.error.as_deref(), p.index), (Some("venue down"), Some(0.005)));
+        clock.advance_ms(60_000);
+        let r = oracle.tick_once().await;
+        assert_eq!(r.premium.unwrap().index, None);
+        let f = r.funding.unwrap();
+        assert_eq!((f.source.as_str(), f.basis), ("ref-ema", 0.0));
+    }
+
+    #[tokio::test]
+    async fn funding_settles_at_the_utc_boundary_and_predicts_in_between() {
+        let clock = ManualClock::new(1_700_000_000_000); // 22:13:20 UTC
+        let rec = Arc::new(Recorder::default());
+        let cfg = OracleConfig { symbol: "CORN_PERP".into(), expo: -8, ..OracleConfig::default() };
+        let cfds: Vec<Arc<dyn CfdProvider + Send + Sync>> = vec![provider("a", 100.0, &clock), provider("b", 100.0, &clock)];
+        let mut oracle = Oracle::new(cfg, rec.clone(), cfds, FundingEngine::new(0.02, 0.005, 3600))
+            .with_clock(Arc::new(clock.clone()));
+        let kinds = |rec: &Recorder| rec.funding.lock().unwrap().iter().map(|f| (f.kind, f.settle_ms)).collect::<Vec<_>>();
+
+        let f = oracle.tick_once().await.funding.unwrap();
+        assert_eq!((f.next_settle_ms, f.settlement), (1_700_002_800_000, None));
+        clock.advance_ms(2_801_000); // 23:00:01
+        let f = oracle.tick_once().await.funding.unwrap();
+        assert_eq!(f.settlement.map(|s| (s.settle_ms, s.covered_ms)), Some((1_700_002_800_000, 2_800_000)));
+        assert_eq!((f.previous_rate, f.settled_rate, oracle.funding_last_rate), (None, Some(0.0), Some(0.0)));
+        assert_eq!(kinds(&rec), [
+            (FundingKind::Predicted, 1_700_002_800_000),
+            (FundingKind::Settled, 1_700_002_800_000),
+            (FundingKind::Predicted, 1_700_006_400_000),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn suppressed_publishes_are_reported() {
+        use crate::config::PublishPolicy;
+        use crate::publishing::PolicyPublisher;
+
+        let clock = ManualClock::new(1_700_000_000_000);
+        let rec = Arc::new(Recorder::default());
+        let policy = PublishPolicy { deviation_bps: Some(50.0), ..PublishPolicy::default() };
+        let cfg = OracleConfig { symbol: "CORN_PERP".into(), expo: -8, ..OracleConfig::default() };
+        let cfds: Vec<Arc<dyn CfdProvider + Send + Sync>> = vec![provider("a", 100.0, &clock), provider("b", 100.0, &clock)];
+        let mut oracle = Oracle::new(cfg, PolicyPublisher::new("evm", policy, rec.clone()), cfds, FundingEngine::new(0.02, 0.005, 8 * 3600))
+            .with_clock(Arc::new(clock.clone()));
+
+        assert!(oracle.tick_once().await.suppressed.is_empty());
+        clock.advance_ms(1_000);
+        let r = oracle.tick_once().await;
+        let kinds: Vec<&str> = r.suppressed.iter().map(|s| s.kind.as_str()).collect();
+        assert_eq!(kinds, ["index", "consensus", "price_feed"]);
+        assert_eq!((rec.marks.lock().unwrap().len(), rec.funding.lock().unwrap().len()), (1, 2));
+        assert_eq!(rec.health.lock().unwrap().len(), 2); // health always goes out
+    }
+
+    #[test]
+    fn apply_config_keeps_runtime_state() {
+        let cfg = OracleConfig { symbol: "CORN_PERP".into(), expo: -8, ..OracleConfig::default() };
+        let mut oracle = Oracle::new(cfg.clone(), StdoutPublisher {}, vec![], FundingEngine::new(0.02, 0.005, 8 * 3600));
+        let mark = IndexTick { symbol: "CORN_PERP".into(), price: 4.5, expo: -8, ts_ms: 1, source: "cfd-consensus", window_sec: 0 };
+        oracle.last_good_mark = Some(mark);
+        oracle.risk.eval_circuit_breaker(4.5, 1);
+        oracle.funding_ref_ema.update(4.5);
+
+        oracle.apply_config(OracleConfig { cfd_mad_k: 9.0, circuit_breaker_per_min: 0.2, ..cfg.clone() }).unwrap();
+        oracle.apply_funding(&FundingConfig { ema_alpha: 0.5, ..FundingConfig::default() });
+        assert_eq!(oracle.cfg.cfd_mad_k, 9.0);
+        assert_eq!(oracle.risk.breaker.cfg.windows[0].max_move, 0.2);
+        assert!(oracle.risk.eval_circuit_breaker(4.5 * 1.25, 2).tripped); // history kept
+        assert_eq!(oracle.last_good_mark.as_ref().unwrap().price, 4.5);
+        assert_eq!((oracle.funding_ref_ema.alpha, oracle.funding_ref_ema.value), (0.5, Some(4.5)));
+
+        assert!(oracle.apply_config(OracleConfig { expo: -10, cfd_mad_k: 1.0, ..cfg }).is_err());
+        assert_eq!(oracle.cfg.cfd_mad_k, 9.0);
+
+        // The reference EMA starts out with the alpha of the engine it was built with.
+        let engine = FundingEngine::from_config(&FundingConfig { ema_alpha: 0.25, ..FundingConfig::default() });
+        let fresh = Oracle::new(OracleConfig { symbol: "CORN_PERP".into(), expo: -8, ..OracleConfig::default() }, StdoutPublisher {}, vec![], engine);
+        assert_eq!(fresh.funding_ref_ema.alpha, 0.25);
+    }
 }
@@ -0,0 +1,100 @@
+// src/premium.rs
+//! Perp premium index: where our perp trades against the index.
+//!
+//! Each sample compares the venue's impact prices with the mark:
+//! `(max(0, impact_bid − index) − max(0, index − impact_ask)) / index`, so it is
+//! zero while the index sits inside the impact spread and otherwise measures
+//! how far a trade of the impact notional would fill from it. Samples are taken
+//! at most every `sample_ms`; the premium index is their mean over the last
+//! `window_ms`, and funding uses it as its basis.
+
+use std::collections::VecDeque;
+
+use crate::config::PerpVenueConfig;
+use crate::types::ImpactPrices;
+
+/// The premium of `impact` over `index`, as a fraction of `index`.
+pub fn premium(impact: &ImpactPrices, index: f64) -> f64 {
+    ((impact.impact_bid - index).max(0.0) - (index - impact.impact_ask).max(0.0)) / index
+}
+
+#[derive(Debug, Clone)]
+pub struct PremiumIndex {
+    pub sample_ms: u64,
+    pub window_ms: u64,
+    samples: VecDeque<(i64, f64)>, // (ts_ms, premium), oldest first
+}
+
+impl PremiumIndex {
+    pub fn new(cfg: &PerpVenueConfig) -> Self {
+        Self { sample_ms: cfg.sample_ms, window_ms: cfg.window_ms, samples: VecDeque::new() }
+    }
+
+    /// Whether a sample taken at `now_ms` would be kept.
+    pub fn due(&self, now_ms: i64) -> bool {
+        !self.samples.back().is_some_and(|(t, _)| now_ms - t < self.sample_ms as i64)
+    }
+
+    /// Record the premium of `impact` over `index` at `now_ms` and return it.
+    pub fn sample(&mut self, impact: &ImpactPrices, index: f64, now_ms: i64) -> f64 {
+        let p = premium(impact, index);
+        self.samples.push_back((now_ms, p));
+        self.prune(now_ms);
+        p
+    }
+
+    /// Mean premium over the window ending at `now_ms`; `None` without samples in it.
+    pub fn value(&self, now_ms: i64) -> Option<f64> {
+        let from = now_ms - self.window_ms as i64;
+        let (n, sum) = self.samples.iter().filter(|(t, _)| *t > from).fold((0, 0.0), |(n, s), (_, p)| (n + 1, s + p));
+        (n > 0).then(|| sum / n as f64)
+    }
+
+    /// Samples within the window ending at `now_ms`.
+    pub fn count(&self, now_ms: i64) -> usize {
+        self.samples.iter().filter(|(t, _)| *t > now_ms - self.window_ms as i64).count()
+    }
+
+    pub fn samples(&self) -> Vec<(i64, f64)> { self.samples.iter().copied().collect() }
+
+    /// Put back samples from `samples` (see `state`); out-of-window ones are pruned as time moves on.
+    pub fn restore(&mut self, samples: Vec<(i64, f64)>) {
+        self.samples = samples.into();
+    }
+
+    fn prune(&mut self, now_ms: i64) {
+        while self.samples.front().is_some_and(|(t, _)| now_ms - t >= self.window_ms as i64) {
+            self.samples.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OrderBook;
+
+    #[test]
+    fn impact_prices_and_premium_window() {
+        let book = OrderBook { bids: vec![(101.0, 50.0), (100.0, 100.0)], asks: vec![(102.0, 10.0), (103.0, 100.0)], ts_ms: 0 };
+        let impact = book.impact_prices(5_050.0).unwrap();
+        assert_eq!(impact.impact_bid, 101.0);
+        // 1020 at 102, the remaining 4030 at 103
+        assert!((impact.impact_ask - 5_050.0 / (10.0 + 4_030.0 / 103.0)).abs() < 1e-9);
+        assert!(book.impact_prices(1e9).is_none());
+
+        // Index inside the impact spread: no premium. Above/below it: the gap.
+        assert_eq!(premium(&impact, 101.5), 0.0);
+        assert!((premium(&impact, 100.0) - 0.01).abs() < 1e-12);
+        assert!(premium(&impact, 105.0) < 0.0);
+
+        let mut idx = PremiumIndex::new(&PerpVenueConfig { window_ms: 3_000, ..PerpVenueConfig::new("venue") });
+        assert!(idx.value(0).is_none());
+        idx.sample(&impact, 100.0, 0);
+        assert!(!idx.due(500));
+        idx.sample(&impact, 101.5, 1_000);
+        assert!((idx.value(1_000).unwrap() - 0.005).abs() < 1e-12);
+        idx.sample(&impact, 101.5, 3_000); // the first sample leaves the window
+        assert_eq!((idx.value(3_000), idx.count(3_000)), (Some(0.0), 2));
+    }
+}
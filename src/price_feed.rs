@@ -0,0 +1,97 @@
+// src/price_feed.rs
+//! Turns marks + consensus stats into Pyth-style `PriceFeed`s.
+//!
+//! The EMA follows Pyth's approach: each sample is weighted by `1/conf`, so
+//! wide (uncertain) marks move the average less. Decay is time-based,
+//! `alpha = 1 - exp(-dt / window)`, so irregular tick spacing is handled.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{scale_by_expo, ConsensusStats, IndexTick, PriceFeed};
+
+/// The EMA accumulators of a `PriceFeedAggregator`, for restoring after a restart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PriceFeedState {
+    pub num_price: f64,
+    pub num_conf: f64,
+    pub den: f64,
+    pub last_ts_ms: Option<i64>,
+}
+
+pub struct PriceFeedAggregator {
+    pub window_ms: f64,
+    num_price: f64, // Σ w·price / conf
+    num_conf: f64,  // Σ w (conf/conf)
+    den: f64,       // Σ w / conf
+    last_ts_ms: Option<i64>,
+}
+
+impl PriceFeedAggregator {
+    /// `window_sec`: EMA time constant (Pyth uses ~1h).
+    pub fn new(window_sec: u32) -> Self {
+        Self { window_ms: window_sec.max(1) as f64 * 1000.0, num_price: 0.0, num_conf: 0.0, den: 0.0, last_ts_ms: None }
+    }
+
+    pub fn state(&self) -> PriceFeedState {
+        PriceFeedState { num_price: self.num_price, num_conf: self.num_conf, den: self.den, last_ts_ms: self.last_ts_ms }
+    }
+
+    pub fn restore(&mut self, s: PriceFeedState) {
+        (self.num_price, self.num_conf, self.den, self.last_ts_ms) = (s.num_price, s.num_conf, s.den, s.last_ts_ms);
+    }
+
+    pub fn update(&mut self, tick: &IndexTick, stats: &ConsensusStats) -> Result<PriceFeed, &'static str> {
+        // Avoid infinite weights on a zero-width band.
+        let conf = stats.conf.max(tick.price.abs() * 1e-9).max(f64::MIN_POSITIVE);
+        let alpha = match self.last_ts_ms {
+            None => 1.0,
+            Some(prev) => {
+                let dt = (tick.ts_ms - prev).max(0) as f64;
+                1.0 - (-dt / self.window_ms).exp()
+            }
+        };
+        let inv = 1.0 / conf;
+        self.num_price = (1.0 - alpha) * self.num_price + alpha * tick.price * inv;
+        self.num_conf = (1.0 - alpha) * self.num_conf + alpha;
+        self.den = (1.0 - alpha) * self.den + alpha * inv;
+        self.last_ts_ms = Some(tick.ts_ms);
+
+        let ema_price = self.num_price / self.den;
+        let ema_conf = self.num_conf / self.den;
+
+        let price = scale_by_expo(tick.price, tick.expo)?;
+        let ema = scale_by_expo(ema_price, tick.expo)?;
+        Ok(PriceFeed {
+            symbol: tick.symbol.clone(),
+            price: i64::try_from(price).map_err(|_| "price overflows i64")?,
+            conf: scale_by_expo(stats.conf, tick.expo)?,
+            expo: tick.expo as i32,
+            publish_time: tick.ts_ms.div_euclid(1000),
+            ema_price: i64::try_from(ema).map_err(|_| "ema overflows i64")?,
+            ema_conf: scale_by_expo(ema_conf, tick.expo)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(conf: f64) -> ConsensusStats {
+        ConsensusStats { n_fresh: 3, n_used: 3, n_dropped: 0, spread_bps: 5, confidence: 0.9, conf }
+    }
+
+    #[test]
+    fn ema_weights_by_inverse_conf() {
+        let mut agg = PriceFeedAggregator::new(60);
+        let f = agg.update(&IndexTick::fixture("GOLD_PERP", 100.0, 0), &stats(0.5)).unwrap();
+        assert_eq!((f.price, f.conf, f.expo, f.ema_price, f.ema_conf), (10_000_000_000, 50_000_000, -8, 10_000_000_000, 50_000_000));
+
+        // One window later alpha = 1 - 1/e; the wide sample barely moves the EMA.
+        let f = agg.update(&IndexTick::fixture("GOLD_PERP", 110.0, 60_000), &stats(5.0)).unwrap();
+        assert_eq!(f.price, 11_000_000_000);
+        assert_eq!(f.publish_time, 60);
+        assert!(f.ema_price > 10_000_000_000 && f.ema_price < 10_200_000_000, "{}", f.ema_price);
+        assert!(f.ema_conf > 50_000_000 && f.ema_conf < 200_000_000, "{}", f.ema_conf);
+    }
+}
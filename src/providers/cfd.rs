@@ -53,6 +53,12 @@ impl NinjasCfd {
         })
     }
 
+    /// Per-request timeout (covers each attempt, not the whole retry ladder).
+    pub fn with_timeout(mut self, timeout: Duration) -> Result<Self> {
+        self.client = Client::builder().user_agent("autonom-oracle/1.0").timeout(timeout).build()?;
+        Ok(self)
+    }
+
     fn map_symbol<'a>(&'a self, symbol: &str) -> Result<&'a str> {
         self.sym_map
             .get(symbol)
@@ -63,6 +69,7 @@ impl NinjasCfd {
 
 #[derive(Debug, Deserialize)]
 struct NinjasResp {
+    #[allow(dead_code)]
     name: String,
     price: f64,
     updated: i64, // unix seconds
@@ -91,6 +98,7 @@ impl CfdProvider for NinjasCfd {
             }
 
             // Build request (use mock base_url in tests)
+            #[allow(unused_mut)]
             let mut req = self
                 .client
                 .get(format!("{}/v1/commodityprice?name={}", self.base_url, ninjas_name))
@@ -101,6 +109,8 @@ impl CfdProvider for NinjasCfd {
             {
                 req = req.header("X-Test-Attempt", i.to_string());
             }
+            #[cfg(not(test))]
+            let _ = i;
 
             let resp = match req.send().await {
                 Ok(r) => r,
@@ -173,8 +183,7 @@ mod tests {
     fn client_pointing_to(server: &MockServer) -> NinjasCfd {
         std::env::set_var("API_NINJAS_API_KEY", "test_key");
         std::env::set_var("API_NINJAS_BASE_URL", server.base_url());
-        let c = NinjasCfd::from_env().unwrap();
-        c
+        NinjasCfd::from_env().unwrap()
     }
 
     #[tokio::test]
@@ -192,7 +201,7 @@ mod tests {
                 .body(r#"{"exchange":"CME","name":"Lean Hogs Futures","price":89.5,"updated":1700000000}"#);
         }).await;
 
-        let mut ninjas = client_pointing_to(&server);
+        let ninjas = client_pointing_to(&server);
         // Ensure symbol map contains LEAN_HOGS_PERP in your real code
         let q = ninjas.latest("LEAN_HOGS_PERP").await.unwrap();
         assert_eq!(q.price, 89.5);
@@ -1,12 +1,14 @@
 // src/providers/cme.rs
-use super::{CmeProvider, ProviderError};
+use super::CmeProvider;
 use crate::types::FuturesLeg;
 
+/// Placeholder legs (front 0.90, next 0.92) stamped with the wall clock; for wiring
+/// tests only, and not selectable from `[cme]` outside them.
 pub struct DummyCme;
 
 #[async_trait::async_trait]
 impl CmeProvider for DummyCme {
-    async fn latest_f1_f2(&self, _symbol: &str) -> Result<(FuturesLeg, FuturesLeg), ProviderError> {
+    async fn latest_f1_f2(&self, _symbol: &str) -> Result<(FuturesLeg, FuturesLeg), anyhow::Error> {
         // TODO: wire to your CME market data (Polygon-like or direct)
         let now = chrono::Utc::now().timestamp_millis();
         Ok((
@@ -14,5 +16,5 @@ impl CmeProvider for DummyCme {
             FuturesLeg { price: 0.92, ts_ms: now, expiry_ts_ms: now + 50 * 86_400_000 },
         ))
     }
+    fn name(&self) -> &'static str { "dummy" }
 }
-
@@ -1,7 +1,11 @@
 // src/providers/mod.rs
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
 
-use crate::types::CfdQuote;
+use crate::config::{ms, CfdProviderConfig, CmeProviderConfig, PerpVenueConfig};
+use crate::types::{CfdQuote, FuturesLeg, ImpactPrices, OrderBook};
 
 #[async_trait]
 pub trait CfdProvider: Send + Sync {
@@ -12,7 +16,98 @@ pub trait CfdProvider: Send + Sync {
 
 #[async_trait]
 pub trait CmeProvider: Send + Sync {
-    async fn latest_f1_f2(&self, symbol: &str) -> Result<CfdQuote, anyhow::Error>;
+    /// Return the front- and next-month futures legs for the symbol.
+    async fn latest_f1_f2(&self, symbol: &str) -> Result<(FuturesLeg, FuturesLeg), anyhow::Error>;
+    fn name(&self) -> &'static str;
+}
+
+/// The venue our perp trades on; its book gives the premium index funding is based on.
+#[async_trait]
+pub trait PerpVenue: Send + Sync {
+    /// Return the perp's current order book.
+    async fn order_book(&self, symbol: &str) -> Result<OrderBook, anyhow::Error>;
+
+    /// Impact bid/ask for `notional`. Venues that publish impact prices directly
+    /// can override this; the default walks `order_book`.
+    async fn impact_prices(&self, symbol: &str, notional: f64) -> Result<ImpactPrices, anyhow::Error> {
+        self.order_book(symbol)
+            .await?
+            .impact_prices(notional)
+            .ok_or_else(|| anyhow::anyhow!("book too thin for {notional} impact notional"))
+    }
+
+    fn name(&self) -> &'static str;
 }
 
 pub mod cfd;
+pub mod cme;
+#[cfg(test)]
+pub mod perp;
+
+/// How the oracle polls one provider (from its `[[cfd_providers]]` entry).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProviderOpts {
+    pub timeout: Option<Duration>, // None: only the tick deadline applies
+    pub weight: f64,               // multiplies the quote's consensus weight
+    pub enabled: bool,
+}
+
+impl Default for ProviderOpts {
+    fn default() -> Self { Self { timeout: None, weight: 1.0, enabled: true } }
+}
+
+impl From<&CfdProviderConfig> for ProviderOpts {
+    fn from(c: &CfdProviderConfig) -> Self {
+        Self { timeout: Some(ms(c.timeout_ms)), weight: c.weight, enabled: c.enabled }
+    }
+}
+
+/// Names accepted in `[[cfd_providers]] name = ...`.
+pub const CFD_PROVIDER_NAMES: &[&str] = &["api-ninjas", "ninjas", "openwebninja", "owninja"];
+
+/// Build the provider a `[[cfd_providers]]` entry refers to.
+pub fn cfd_provider_from_config(cfg: &CfdProviderConfig) -> anyhow::Result<Arc<dyn CfdProvider + Send + Sync>> {
+    match cfg.name.as_str() {
+        "api-ninjas" | "ninjas" => Ok(Arc::new(cfd::NinjasCfd::from_env()?.with_timeout(ms(cfg.timeout_ms))?)),
+        "openwebninja" | "owninja" => Ok(Arc::new(cfd::OwninjaCfd)),
+        other => anyhow::bail!("unknown cfd provider `{other}`"),
+    }
+}
+
+impl From<&CmeProviderConfig> for ProviderOpts {
+    fn from(c: &CmeProviderConfig) -> Self {
+        Self { timeout: Some(ms(c.timeout_ms)), ..Self::default() }
+    }
+}
+
+/// Names accepted in `[cme] name = ...`. No real futures source is wired up yet;
+/// `dummy` (`cme::DummyCme`, fixed placeholder legs) is accepted in tests only.
+pub const CME_PROVIDER_NAMES: &[&str] = if cfg!(test) { &["dummy"] } else { &[] };
+
+/// Build the provider the `[cme]` section refers to.
+pub fn cme_provider_from_config(cfg: &CmeProviderConfig) -> anyhow::Result<Arc<dyn CmeProvider + Send + Sync>> {
+    #[cfg(test)]
+    if cfg.name == "dummy" {
+        return Ok(Arc::new(cme::DummyCme));
+    }
+    anyhow::bail!("unknown cme provider `{}`", cfg.name)
+}
+
+impl From<&PerpVenueConfig> for ProviderOpts {
+    fn from(c: &PerpVenueConfig) -> Self {
+        Self { timeout: Some(ms(c.timeout_ms)), ..Self::default() }
+    }
+}
+
+/// Names accepted in `[perp_venue] name = ...`. No real venue is wired up yet;
+/// `dummy` (a fixed placeholder book) is accepted in tests only.
+pub const PERP_VENUE_NAMES: &[&str] = if cfg!(test) { &["dummy"] } else { &[] };
+
+/// Build the venue the `[perp_venue]` section refers to.
+pub fn perp_venue_from_config(cfg: &PerpVenueConfig) -> anyhow::Result<Arc<dyn PerpVenue + Send + Sync>> {
+    #[cfg(test)]
+    if cfg.name == "dummy" {
+        return Ok(Arc::new(perp::DummyVenue));
+    }
+    anyhow::bail!("unknown perp venue `{}`", cfg.name)
+}
@@ -0,0 +1,431 @@
+// src/publishing/evm.rs
+//! Push marks to an EVM price-feed contract:
+//!
+//!   function updatePrice(bytes32 feedId, int64 price, int8 expo, uint64 ts)
+//!
+//! `feedId` is `keccak256(symbol)`, `price` the tick scaled by `expo`, `ts` the
+//! tick time in unix seconds. Transactions are EIP-1559, signed locally, with the
+//! nonce tracked here (resynced from the node after a failed push). A push that
+//! isn't mined within `replace_after` is re-sent with the same nonce and bumped
+//! fees, up to `max_replacements` times, until `receipt_timeout`.
+//!
+//! Pushes run in the background, one at a time per symbol (see `inflight`): a
+//! tick arriving meanwhile goes out when that symbol's push finishes, and only
+//! the nonce is shared between symbols, so a slow confirmation on one market
+//! doesn't hold up the others.
+
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::{EthereumWallet, TransactionBuilder};
+use alloy::primitives::{keccak256, Address, Bytes, TxHash, B256};
+use alloy::providers::{Provider, RootProvider};
+use alloy::rpc::types::{TransactionReceipt, TransactionRequest};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use anyhow::{anyhow, Context, Result};
+use tokio::sync::Mutex;
+
+use super::inflight::InFlight;
+use super::policy::Suppressed;
+use super::Publisher;
+use crate::types::{scale_by_expo, FundingUpdate, IndexTick};
+
+sol! {
+    interface IPriceFeed {
+        function updatePrice(bytes32 feedId, int64 price, int8 expo, uint64 ts) external;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EvmConfig {
+    pub rpc_url: String,
+    pub contract: Address,
+    /// Fetched from the node on first use when `None`.
+    pub chain_id: Option<u64>,
+    /// Headroom on top of `eth_estimateGas` (1.2 = +20%).
+    pub gas_limit_multiplier: f64,
+    /// Fee bump per replacement, in percent. Nodes require >= 10.
+    pub fee_bump_pct: u64,
+    pub replace_after: Duration,
+    pub max_replacements: u32,
+    pub receipt_timeout: Duration,
+    pub poll_interval: Duration,
+}
+
+impl EvmConfig {
+    pub fn new(rpc_url: impl Into<String>, contract: Address) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            contract,
+            chain_id: None,
+            gas_limit_multiplier: 1.2,
+            fee_bump_pct: 15,
+            replace_after: Duration::from_secs(30),
+            max_replacements: 3,
+            receipt_timeout: Duration::from_secs(180),
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// What a confirmed push ended up costing.
+#[derive(Debug, Clone)]
+pub struct PushReceipt {
+    pub tx_hash: TxHash,
+    pub nonce: u64,
+    pub gas_limit: u64,
+    pub replacements: u32,
+    pub block_number: Option<u64>,
+}
+
+pub fn feed_id(symbol: &str) -> B256 {
+    keccak256(symbol.as_bytes())
+}
+
+/// ABI-encoded `updatePrice` call data for a tick.
+pub fn update_price_calldata(tick: &IndexTick) -> Result<Bytes> {
+    let scaled = scale_by_expo(tick.price, tick.expo).map_err(|e| anyhow!(e))?;
+    let price = i64::try_from(scaled).map_err(|_| anyhow!("scaled price overflows int64"))?;
+    let call = IPriceFeed::updatePriceCall {
+        feedId: feed_id(&tick.symbol),
+        price,
+        expo: tick.expo,
+        ts: (tick.ts_ms / 1000).max(0) as u64,
+    };
+    Ok(call.abi_encode().into())
+}
+
+fn bump(v: u128, pct: u64) -> u128 {
+    v + v * pct.max(10) as u128 / 100 + 1
+}
+
+struct EvmInner {
+    cfg: EvmConfig,
+    provider: RootProvider,
+    wallet: EthereumWallet,
+    from: Address,
+    chain_id: Mutex<Option<u64>>,
+    /// Next nonce to hand out; `None` = ask the node.
+    nonce: Mutex<Option<u64>>,
+    queue: InFlight<IndexTick>,
+}
+
+pub struct EvmPublisher {
+    inner: Arc<EvmInner>,
+}
+
+impl EvmPublisher {
+    pub fn new(cfg: EvmConfig, signer: PrivateKeySigner) -> Result<Self> {
+        let url = reqwest::Url::parse(&cfg.rpc_url).context("parsing EVM rpc url")?;
+        let from = signer.address();
+        Ok(Self {
+            inner: Arc::new(EvmInner {
+                provider: RootProvider::new_http(url),
+                wallet: EthereumWallet::from(signer),
+                from,
+                chain_id: Mutex::new(cfg.chain_id),
+                nonce: Mutex::new(None),
+                queue: InFlight::new("evm"),
+                cfg,
+            }),
+        })
+    }
+
+    /// Reads `EVM_RPC_URL`, `EVM_FEED_CONTRACT`, `EVM_PRIVATE_KEY` and optional `EVM_CHAIN_ID`.
+    pub fn from_env() -> Result<Self> {
+        let rpc_url = std::env::var("EVM_RPC_URL").map_err(|_| anyhow!("Set EVM_RPC_URL"))?;
+        let contract = std::env::var("EVM_FEED_CONTRACT").map_err(|_| anyhow!("Set EVM_FEED_CONTRACT"))?;
+        let key = std::env::var("EVM_PRIVATE_KEY").map_err(|_| anyhow!("Set EVM_PRIVATE_KEY"))?;
+        let mut cfg = EvmConfig::new(rpc_url, Address::from_str(&contract).context("EVM_FEED_CONTRACT")?);
+        if let Ok(id) = std::env::var("EVM_CHAIN_ID") {
+            cfg.chain_id = Some(id.parse().context("EVM_CHAIN_ID")?);
+        }
+        let signer = PrivateKeySigner::from_str(&key).map_err(|_| anyhow!("invalid EVM_PRIVATE_KEY"))?;
+        Self::new(cfg, signer)
+    }
+
+    pub fn sender(&self) -> Address { self.inner.from }
+
+    /// Submit one update and wait for its receipt (replacing it if stuck).
+    pub async fn push(&self, tick: &IndexTick) -> Result<PushReceipt> {
+        self.inner.push(tick).await
+    }
+}
+
+impl EvmInner {
+    async fn chain_id(&self) -> Result<u64> {
+        let mut cached = self.chain_id.lock().await;
+        if let Some(id) = *cached {
+            return Ok(id);
+        }
+        let id = self.provider.get_chain_id().await?;
+        *cached = Some(id);
+        Ok(id)
+    }
+
+    /// Take the next nonce, asking the node if we don't know it.
+    async fn next_nonce(&self) -> Result<u64> {
+        let mut slot = self.nonce.lock().await;
+        let nonce = match *slot {
+            Some(n) => n,
+            None => self.provider.get_transaction_count(self.from).pending().await?,
+        };
+        *slot = Some(nonce + 1);
+        Ok(nonce)
+    }
+
+    async fn push(&self, tick: &IndexTick) -> Result<PushReceipt> {
+        let input = update_price_calldata(tick)?;
+        let chain_id = self.chain_id().await?;
+        let base = TransactionRequest::default()
+            .with_from(self.from)
+            .with_to(self.cfg.contract)
+            .with_input(input)
+            .with_chain_id(chain_id);
+        let estimate = self.provider.estimate_gas(&base).await?;
+        let gas_limit = (estimate as f64 * self.cfg.gas_limit_multiplier.max(1.0)).ceil() as u64;
+        let fees = self.provider.estimate_eip1559_fees(None).await?;
+
+        let nonce = self.next_nonce().await?;
+        let r = self.send(base.with_nonce(nonce), nonce, gas_limit, fees.max_fee_per_gas, fees.max_priority_fee_per_gas).await;
+        if r.is_err() {
+            // The nonce may be unused or taken by someone else; resync before the next push.
+            *self.nonce.lock().await = None;
+        }
+        r
+    }
+
+    /// Send `base` with `nonce`, replacing it with bumped fees until it is mined.
+    async fn send(&self, base: TransactionRequest, nonce: u64, gas_limit: u64, max_fee: u128, priority: u128) -> Result<PushReceipt> {
+        let (mut max_fee, mut priority) = (max_fee, priority);
+        let started = tokio::time::Instant::now();
+        let timeout_at = started + self.cfg.receipt_timeout;
+        let mut sent: Vec<TxHash> = Vec::new();
+        let mut replacements = 0u32;
+        loop {
+            let envelope = base
+                .clone()
+                .with_gas_limit(gas_limit)
+                .with_max_fee_per_gas(max_fee)
+                .with_max_priority_fee_per_gas(priority)
+                .build(&self.wallet)
+                .await?;
+            let hash = *envelope.tx_hash();
+            match self.provider.send_raw_transaction(&envelope.encoded_2718()).await {
+                Ok(_) => sent.push(hash),
+                Err(e) => {
+                    let msg = e.to_string().to_lowercase();
+                    if msg.contains("already known") {
+                        sent.push(hash);
+                    } else if msg.contains("nonce too low") {
+                        // Someone else used the nonce (or a replaced tx landed).
+                        return Err(anyhow!("nonce {nonce} already used: {e}"));
+                    } else if !msg.contains("underpriced") || sent.is_empty() {
+                        return Err(e.into());
+                    }
+                    // underpriced replacement: keep waiting on earlier txs, bump again below
+                }
+            }
+
+            // Wait for any of the txs sent so far, until it's time to replace (or give up).
+            let deadline = if replacements < self.cfg.max_replacements {
+                (started + self.cfg.replace_after * (replacements + 1)).min(timeout_at)
+            } else {
+                timeout_at
+            };
+            if let Some((hash, receipt)) = self.wait_for_receipt(&sent, deadline).await? {
+                if !receipt.status() {
+                    return Err(anyhow!("updatePrice reverted in {hash}"));
+                }
+                return Ok(PushReceipt {
+                    tx_hash: hash,
+                    nonce,
+                    gas_limit,
+                    replacements,
+                    block_number: receipt.block_number,
+                });
+            }
+            if tokio::time::Instant::now() >= timeout_at {
+                return Err(anyhow!(
+                    "no receipt for nonce {nonce} after {:?} ({} txs sent)",
+                    self.cfg.receipt_timeout,
+                    sent.len()
+                ));
+            }
+
+            replacements += 1;
+            max_fee = bump(max_fee, self.cfg.fee_bump_pct);
+            priority = bump(priority, self.cfg.fee_bump_pct);
+            tracing::warn!(nonce, replacements, max_fee, priority, "evm push stuck, replacing");
+        }
+    }
+
+    async fn wait_for_receipt(
+        &self,
+        sent: &[TxHash],
+        deadline: tokio::time::Instant,
+    ) -> Result<Option<(TxHash, TransactionReceipt)>> {
+        loop {
+            if let Some(found) = self.find_receipt(sent).await? {
+                return Ok(Some(found));
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(self.cfg.poll_interval).await;
+        }
+    }
+
+    async fn find_receipt(&self, sent: &[TxHash]) -> Result<Option<(TxHash, TransactionReceipt)>> {
+        for h in sent {
+            if let Some(r) = self.provider.get_transaction_receipt(*h).await? {
+                return Ok(Some((*h, r)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[async_trait::async_trait]
+impl Publisher for EvmPublisher {
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        let symbol = tick.symbol.clone();
+        let Some(tick) = self.inner.queue.offer(&symbol, tick) else {
+            tracing::debug!("evm push for {symbol} in flight, holding the latest tick");
+            return Ok(());
+        };
+        let inner = self.inner.clone();
+        tokio::spawn(async move {
+            let mut next = Some(tick);
+            while let Some(tick) = next {
+                match inner.push(&tick).await {
+                    Ok(r) => tracing::info!("evm push {} nonce={} tx={}", tick.symbol, r.nonce, r.tx_hash),
+                    Err(e) => tracing::warn!("evm push failed for {}: {e:?}", tick.symbol),
+                }
+                next = inner.queue.finish(&symbol);
+            }
+        });
+        Ok(())
+    }
+
+    fn take_suppressed(&self, symbol: &str) -> Vec<Suppressed> {
+        self.inner.queue.take_skipped(symbol)
+    }
+
+    /// Funding is not mirrored on-chain.
+    async fn publish_funding(&self, _fu: FundingUpdate) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::POST, MockServer};
+
+    const KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    fn tick() -> IndexTick {
+        IndexTick::fixture("LEAN_HOGS_PERP", 0.9123, 1_700_000_000_500)
+    }
+
+    fn rpc_ok(result: serde_json::Value) -> String {
+        serde_json::json!({ "jsonrpc": "2.0", "id": 0, "result": result }).to_string()
+    }
+
+    /// Mocks every call a push makes; returns the `eth_sendRawTransaction` mock.
+    async fn mock_node(server: &MockServer, receipt: serde_json::Value) -> httpmock::Mock<'_> {
+        let replies = [
+            ("eth_getTransactionCount", serde_json::json!("0x5")),
+            ("eth_estimateGas", serde_json::json!("0x7530")),
+            ("eth_feeHistory", serde_json::json!({
+                "oldestBlock": "0x1",
+                "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00"],
+                "gasUsedRatio": [0.5],
+                "reward": [["0x5f5e100"]],
+            })),
+            ("eth_getTransactionReceipt", receipt),
+        ];
+        for (method, result) in replies {
+            server.mock_async(|when, then| {
+                when.method(POST).body_contains(format!("\"{method}\""));
+                then.status(200).header("content-type", "application/json").body(rpc_ok(result));
+            }).await;
+        }
+        server.mock_async(|when, then| {
+            when.method(POST).body_contains("\"eth_sendRawTransaction\"");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(rpc_ok(serde_json::json!(format!("0x{}", "ab".repeat(32)))));
+        }).await
+    }
+
+    fn publisher(server: &MockServer) -> EvmPublisher {
+        let mut cfg = EvmConfig::new(server.base_url(), Address::repeat_byte(0x11));
+        cfg.chain_id = Some(31337);
+        cfg.replace_after = Duration::from_millis(60);
+        cfg.max_replacements = 2;
+        cfg.receipt_timeout = Duration::from_millis(400);
+        cfg.poll_interval = Duration::from_millis(10);
+        EvmPublisher::new(cfg, PrivateKeySigner::from_str(KEY).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn calldata_layout() {
+        let data = update_price_calldata(&tick()).unwrap();
+        let call = IPriceFeed::updatePriceCall::abi_decode(&data, true).unwrap();
+        assert_eq!(call.feedId, keccak256(b"LEAN_HOGS_PERP"));
+        assert_eq!(call.price, 91_230_000);
+        assert_eq!(call.expo, -8);
+        assert_eq!(call.ts, 1_700_000_000);
+    }
+
+    #[tokio::test]
+    async fn push_confirms_and_tracks_nonce() {
+        let server = MockServer::start_async().await;
+        let sends = mock_node(&server, serde_json::json!({
+            "transactionHash": format!("0x{}", "ab".repeat(32)),
+            "transactionIndex": "0x0",
+            "blockHash": format!("0x{}", "cd".repeat(32)),
+            "blockNumber": "0x10",
+            "from": format!("0x{}", "22".repeat(20)),
+            "to": format!("0x{}", "11".repeat(20)),
+            "cumulativeGasUsed": "0x7530",
+            "gasUsed": "0x7530",
+            "effectiveGasPrice": "0x3b9aca00",
+            "contractAddress": null,
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "type": "0x2",
+            "status": "0x1",
+        })).await;
+
+        let p = publisher(&server);
+        let r = p.push(&tick()).await.unwrap();
+        assert_eq!(r.nonce, 5);
+        assert_eq!(r.gas_limit, 36_000);
+        assert_eq!(r.replacements, 0);
+        assert_eq!(r.block_number, Some(16));
+
+        // Second push reuses the tracked nonce instead of asking the node again.
+        let r2 = p.push(&tick()).await.unwrap();
+        assert_eq!(r2.nonce, 6);
+        assert_eq!(sends.hits_async().await, 2);
+    }
+
+    #[tokio::test]
+    async fn stuck_push_is_replaced_then_times_out() {
+        let server = MockServer::start_async().await;
+        let sends = mock_node(&server, serde_json::Value::Null).await;
+
+        let p = publisher(&server);
+        let err = p.push(&tick()).await.unwrap_err();
+        assert!(err.to_string().contains("no receipt for nonce 5"), "{err}");
+        // original + 2 fee-bumped replacements
+        assert_eq!(sends.hits_async().await, 3);
+    }
+}
@@ -0,0 +1,89 @@
+// src/publishing/inflight.rs
+//! One chain push at a time per symbol.
+//!
+//! The on-chain sinks push in the background so the oracle loop never waits on
+//! a confirmation. `InFlight` tracks which symbols have a push running; an
+//! update arriving meanwhile waits as that symbol's pending one and goes out
+//! when the push finishes. A newer update replaces the pending one, and each
+//! replaced update is counted in `publish_skipped_total` and handed back
+//! through `take_skipped` for the audit trail. Other symbols are unaffected.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::policy::Suppressed;
+use crate::metrics;
+
+struct Slot<T> {
+    busy: bool,
+    pending: Option<T>,
+    skipped: Vec<Suppressed>,
+}
+
+impl<T> Default for Slot<T> {
+    fn default() -> Self { Self { busy: false, pending: None, skipped: Vec::new() } }
+}
+
+pub struct InFlight<T> {
+    pub sink: &'static str,
+    slots: Mutex<HashMap<String, Slot<T>>>, // by symbol
+}
+
+impl<T> InFlight<T> {
+    pub fn new(sink: &'static str) -> Self { Self { sink, slots: Mutex::default() } }
+
+    /// Offer `item` for `symbol`. Returns it if the caller should push it now;
+    /// otherwise it waits for the running push, replacing any pending update.
+    pub fn offer(&self, symbol: &str, item: T) -> Option<T> {
+        let mut slots = self.slots.lock().unwrap();
+        let s = slots.entry(symbol.to_string()).or_default();
+        if !s.busy {
+            s.busy = true;
+            return Some(item);
+        }
+        if s.pending.replace(item).is_some() {
+            metrics::PUBLISH_SKIPPED_TOTAL.with_label_values(&[symbol, self.sink]).inc();
+            s.skipped.push(Suppressed { sink: self.sink.to_string(), kind: "superseded".into() });
+        }
+        None
+    }
+
+    /// The push for `symbol` finished. Returns the pending update to push next,
+    /// if any; otherwise the symbol is free again.
+    pub fn finish(&self, symbol: &str) -> Option<T> {
+        let mut slots = self.slots.lock().unwrap();
+        let s = slots.entry(symbol.to_string()).or_default();
+        let next = s.pending.take();
+        s.busy = next.is_some();
+        next
+    }
+
+    /// Updates for `symbol` replaced before they could be pushed, since the last call.
+    pub fn take_skipped(&self, symbol: &str) -> Vec<Suppressed> {
+        self.slots.lock().unwrap().get_mut(symbol).map(|s| std::mem::take(&mut s.skipped)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_latest_pending_update_per_symbol() {
+        let q = InFlight::new("evm");
+        assert_eq!(q.offer("CORN", 1), Some(1));
+        assert_eq!(q.offer("GOLD", 10), Some(10)); // another symbol isn't held up
+        assert_eq!(q.offer("CORN", 2), None);
+        assert_eq!(q.offer("CORN", 3), None); // replaces 2
+
+        assert_eq!(q.finish("CORN"), Some(3));
+        assert_eq!(q.offer("CORN", 4), None);
+        assert_eq!(q.finish("CORN"), Some(4));
+        assert_eq!(q.finish("CORN"), None);
+        assert_eq!(q.offer("CORN", 5), Some(5));
+
+        let skipped = q.take_skipped("CORN");
+        assert_eq!(skipped, [Suppressed { sink: "evm".into(), kind: "superseded".into() }]);
+        assert!(q.take_skipped("CORN").is_empty() && q.take_skipped("GOLD").is_empty());
+    }
+}
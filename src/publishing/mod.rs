@@ -0,0 +1,160 @@
+// src/publishing/mod.rs
+use std::sync::Arc;
+
+use crate::signing::SignedIndexTick;
+use crate::health::OracleHealth;
+use crate::types::{CfdQuote, ConsensusStats, IndexTick, FundingUpdate, PriceFeed};
+
+pub mod evm;
+pub mod inflight;
+pub mod policy;
+pub mod solana;
+pub mod sqlite;
+pub mod web2_cache;
+
+pub use evm::EvmPublisher;
+pub use policy::PolicyPublisher;
+pub use solana::SolanaPublisher;
+pub use sqlite::SqlitePublisher;
+pub use web2_cache::Web2CachePublisher;
+
+#[async_trait::async_trait]
+pub trait Publisher: Send + Sync + 'static {
+    /// Publish an index/mark tick (you can sign & persist inside)
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()>;
+    /// Publish funding update snapshots (e.g., every 8h)
+    async fn publish_funding(&self, fu: FundingUpdate) -> anyhow::Result<()>;
+    /// Consensus telemetry for the mark just published. Ignored unless a sink cares.
+    async fn publish_consensus(&self, _symbol: &str, _stats: ConsensusStats) -> anyhow::Result<()> {
+        Ok(())
+    }
+    /// A mark with its oracle-key attestation (see `signing::SigningPublisher`).
+    /// Sinks that don't keep signatures just publish the tick.
+    async fn publish_signed(&self, signed: SignedIndexTick) -> anyhow::Result<()> {
+        self.publish_index(signed.tick).await
+    }
+    /// Raw per-provider quotes collected for `symbol` this tick (before any filtering).
+    async fn publish_quotes(&self, _symbol: &str, _quotes: &[CfdQuote]) -> anyhow::Result<()> {
+        Ok(())
+    }
+    /// Pyth-style price + confidence (with EMAs) for the mark just published.
+    async fn publish_price_feed(&self, _feed: PriceFeed) -> anyhow::Result<()> {
+        Ok(())
+    }
+    /// Market health for this tick; sent every tick, including ones with no new mark.
+    async fn publish_health(&self, _health: OracleHealth) -> anyhow::Result<()> {
+        Ok(())
+    }
+    /// Updates for `symbol` that a sink's publish policy held back since the last
+    /// call (see `policy`). Sinks without one never hold anything back.
+    fn take_suppressed(&self, _symbol: &str) -> Vec<policy::Suppressed> {
+        Vec::new()
+    }
+}
+
+/// Lets several owners (oracle loop, HTTP server, ...) share one sink.
+#[async_trait::async_trait]
+impl<P: Publisher + ?Sized> Publisher for Arc<P> {
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        (**self).publish_index(tick).await
+    }
+    async fn publish_funding(&self, fu: FundingUpdate) -> anyhow::Result<()> {
+        (**self).publish_funding(fu).await
+    }
+    async fn publish_consensus(&self, symbol: &str, stats: ConsensusStats) -> anyhow::Result<()> {
+        (**self).publish_consensus(symbol, stats).await
+    }
+    async fn publish_signed(&self, signed: SignedIndexTick) -> anyhow::Result<()> {
+        (**self).publish_signed(signed).await
+    }
+    async fn publish_quotes(&self, symbol: &str, quotes: &[CfdQuote]) -> anyhow::Result<()> {
+        (**self).publish_quotes(symbol, quotes).await
+    }
+    async fn publish_price_feed(&self, feed: PriceFeed) -> anyhow::Result<()> {
+        (**self).publish_price_feed(feed).await
+    }
+    async fn publish_health(&self, health: OracleHealth) -> anyhow::Result<()> {
+        (**self).publish_health(health).await
+    }
+    fn take_suppressed(&self, symbol: &str) -> Vec<policy::Suppressed> {
+        (**self).take_suppressed(symbol)
+    }
+}
+
+/// Fans every update out to all sinks. A failing sink does not stop the others;
+/// the first error is returned once everyone has been called.
+#[derive(Default)]
+pub struct MultiPublisher {
+    sinks: Vec<Arc<dyn Publisher>>,
+}
+
+impl MultiPublisher {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn with(mut self, sink: Arc<dyn Publisher>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    pub fn push(&mut self, sink: Arc<dyn Publisher>) { self.sinks.push(sink); }
+
+    pub fn len(&self) -> usize { self.sinks.len() }
+
+    pub fn is_empty(&self) -> bool { self.sinks.is_empty() }
+}
+
+fn first_err(results: Vec<anyhow::Result<()>>) -> anyhow::Result<()> {
+    results.into_iter().find(|r| r.is_err()).unwrap_or(Ok(()))
+}
+
+#[async_trait::async_trait]
+impl Publisher for MultiPublisher {
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        let futs = self.sinks.iter().map(|s| s.publish_index(tick.clone()));
+        first_err(futures::future::join_all(futs).await)
+    }
+    async fn publish_funding(&self, fu: FundingUpdate) -> anyhow::Result<()> {
+        let futs = self.sinks.iter().map(|s| s.publish_funding(fu.clone()));
+        first_err(futures::future::join_all(futs).await)
+    }
+    async fn publish_consensus(&self, symbol: &str, stats: ConsensusStats) -> anyhow::Result<()> {
+        let futs = self.sinks.iter().map(|s| s.publish_consensus(symbol, stats));
+        first_err(futures::future::join_all(futs).await)
+    }
+    async fn publish_signed(&self, signed: SignedIndexTick) -> anyhow::Result<()> {
+        let futs = self.sinks.iter().map(|s| s.publish_signed(signed.clone()));
+        first_err(futures::future::join_all(futs).await)
+    }
+    async fn publish_quotes(&self, symbol: &str, quotes: &[CfdQuote]) -> anyhow::Result<()> {
+        let futs = self.sinks.iter().map(|s| s.publish_quotes(symbol, quotes));
+        first_err(futures::future::join_all(futs).await)
+    }
+    async fn publish_price_feed(&self, feed: PriceFeed) -> anyhow::Result<()> {
+        let futs = self.sinks.iter().map(|s| s.publish_price_feed(feed.clone()));
+        first_err(futures::future::join_all(futs).await)
+    }
+    async fn publish_health(&self, health: OracleHealth) -> anyhow::Result<()> {
+        let futs = self.sinks.iter().map(|s| s.publish_health(health.clone()));
+        first_err(futures::future::join_all(futs).await)
+    }
+    fn take_suppressed(&self, symbol: &str) -> Vec<policy::Suppressed> {
+        self.sinks.iter().flat_map(|s| s.take_suppressed(symbol)).collect()
+    }
+}
+
+/// Example in-memory stub. Replace with your Web2 cache/signature path.
+pub struct StdoutPublisher;
+
+#[async_trait::async_trait]
+impl Publisher for StdoutPublisher {
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        println!("[INDEX] {} {}e{} @{} src={} twap={}s",
+            tick.symbol, tick.price, tick.expo, tick.ts_ms, tick.source, tick.window_sec);
+        Ok(())
+    }
+    async fn publish_funding(&self, fu: FundingUpdate) -> anyhow::Result<()> {
+        println!("[FUNDING] {} rate={} interval={}s @{}",
+            fu.symbol, fu.rate, fu.interval_sec, fu.ts_ms);
+        Ok(())
+    }
+}
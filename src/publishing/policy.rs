@@ -0,0 +1,210 @@
+// src/publishing/policy.rs
+//! Deviation-threshold and heartbeat publishing per sink.
+//!
+//! `PolicyPublisher` wraps one sink and decides, per symbol, which marks and
+//! funding updates reach it: a mark goes out when it has moved at least
+//! `deviation_bps` from the last one this sink got, a funding prediction when
+//! its rate has moved `funding_deviation_bps`, and either goes out once
+//! `heartbeat_ms` has passed since the last one regardless. The consensus
+//! stats and price feed follow their mark. Funding settlements, health and raw
+//! quotes always pass.
+//!
+//! Held-back updates are counted in `publish_suppressed_total` and handed back
+//! through `take_suppressed`, so the oracle can record them in the audit trail.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use super::Publisher;
+use crate::config::PublishPolicy;
+use crate::health::OracleHealth;
+use crate::metrics;
+use crate::signing::SignedIndexTick;
+use crate::types::{CfdQuote, ConsensusStats, FundingKind, FundingUpdate, IndexTick, PriceFeed};
+
+/// An update a sink held back: by its publish policy, or replaced by a newer
+/// one while a chain push was in flight (see `inflight`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suppressed {
+    pub sink: String,
+    pub kind: String, // "index", "consensus", "price_feed", "funding"; "superseded" for a chain push (see `inflight`)
+}
+
+impl PublishPolicy {
+    /// Whether `value` at `ts_ms` should go out given the last published
+    /// `(ts_ms, value)`. `moved` is the change in bps. Without a deviation
+    /// threshold only the heartbeat publishes, and without either everything does.
+    fn due(&self, threshold_bps: Option<f64>, last: Option<(i64, f64)>, ts_ms: i64, moved: impl Fn(f64) -> f64) -> bool {
+        let Some((last_ts, last_value)) = last else { return true };
+        if self.heartbeat_ms.is_some_and(|h| ts_ms - last_ts >= h as i64) {
+            return true;
+        }
+        match threshold_bps {
+            Some(bps) => moved(last_value) >= bps,
+            None => self.heartbeat_ms.is_none(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct SymbolState {
+    index: Option<(i64, f64)>,   // last published (ts_ms, price)
+    funding: Option<(i64, f64)>, // last published (ts_ms, rate)
+    index_sent: bool,            // whether the latest mark went out; its stats/feed follow it
+    suppressed: Vec<Suppressed>,
+}
+
+pub struct PolicyPublisher<P> {
+    pub sink: String,
+    pub policy: PublishPolicy,
+    pub inner: P,
+    state: Mutex<HashMap<String, SymbolState>>, // by symbol
+}
+
+impl<P: Publisher> PolicyPublisher<P> {
+    pub fn new(sink: impl Into<String>, policy: PublishPolicy, inner: P) -> Self {
+        Self { sink: sink.into(), policy, inner, state: Mutex::default() }
+    }
+
+    fn gate_index(&self, tick: &IndexTick) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let s = state.entry(tick.symbol.clone()).or_default();
+        let send = self.policy.due(self.policy.deviation_bps, s.index, tick.ts_ms, |last| (tick.price / last - 1.0).abs() * 10_000.0);
+        s.index_sent = send;
+        if send {
+            s.index = Some((tick.ts_ms, tick.price));
+        }
+        self.note(&tick.symbol, s, "index", send)
+    }
+
+    /// The consensus stats and price feed go wherever their mark went.
+    fn gate_follower(&self, symbol: &str, kind: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let s = state.entry(symbol.to_string()).or_default();
+        let send = s.index_sent || s.index.is_none();
+        self.note(symbol, s, kind, send)
+    }
+
+    /// Settlements always go out; predictions are gated.
+    fn gate_funding(&self, fu: &FundingUpdate) -> bool {
+        if fu.kind == FundingKind::Settled {
+            return true;
+        }
+        let mut state = self.state.lock().unwrap();
+        let s = state.entry(fu.symbol.clone()).or_default();
+        let send = self.policy.due(self.policy.funding_deviation_bps, s.funding, fu.ts_ms, |last| (fu.rate - last).abs() * 10_000.0);
+        if send {
+            s.funding = Some((fu.ts_ms, fu.rate));
+        }
+        self.note(&fu.symbol, s, "funding", send)
+    }
+
+    fn note(&self, symbol: &str, s: &mut SymbolState, kind: &str, send: bool) -> bool {
+        if !send {
+            metrics::PUBLISH_SUPPRESSED_TOTAL.with_label_values(&[symbol, &self.sink, kind]).inc();
+            s.suppressed.push(Suppressed { sink: self.sink.clone(), kind: kind.to_string() });
+        }
+        send
+    }
+}
+
+#[async_trait::async_trait]
+impl<P: Publisher> Publisher for PolicyPublisher<P> {
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        if !self.gate_index(&tick) {
+            return Ok(());
+        }
+        self.inner.publish_index(tick).await
+    }
+    async fn publish_funding(&self, fu: FundingUpdate) -> anyhow::Result<()> {
+        if !self.gate_funding(&fu) {
+            return Ok(());
+        }
+        self.inner.publish_funding(fu).await
+    }
+    async fn publish_consensus(&self, symbol: &str, stats: ConsensusStats) -> anyhow::Result<()> {
+        if !self.gate_follower(symbol, "consensus") {
+            return Ok(());
+        }
+        self.inner.publish_consensus(symbol, stats).await
+    }
+    async fn publish_signed(&self, signed: SignedIndexTick) -> anyhow::Result<()> {
+        if !self.gate_index(&signed.tick) {
+            return Ok(());
+        }
+        self.inner.publish_signed(signed).await
+    }
+    async fn publish_quotes(&self, symbol: &str, quotes: &[CfdQuote]) -> anyhow::Result<()> {
+        self.inner.publish_quotes(symbol, quotes).await
+    }
+    async fn publish_price_feed(&self, feed: PriceFeed) -> anyhow::Result<()> {
+        if !self.gate_follower(&feed.symbol, "price_feed") {
+            return Ok(());
+        }
+        self.inner.publish_price_feed(feed).await
+    }
+    async fn publish_health(&self, health: OracleHealth) -> anyhow::Result<()> {
+        self.inner.publish_health(health).await
+    }
+    fn take_suppressed(&self, symbol: &str) -> Vec<Suppressed> {
+        let mut out = self.state.lock().unwrap().get_mut(symbol).map(|s| std::mem::take(&mut s.suppressed)).unwrap_or_default();
+        out.extend(self.inner.take_suppressed(symbol));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Count {
+        index: AtomicUsize,
+        funding: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Publisher for Count {
+        async fn publish_index(&self, _tick: IndexTick) -> anyhow::Result<()> {
+            self.index.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+        async fn publish_funding(&self, _fu: FundingUpdate) -> anyhow::Result<()> {
+            self.funding.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn funding(rate: f64, ts_ms: i64) -> FundingUpdate {
+        FundingUpdate { symbol: "CORN_PERP".into(), rate, interval_sec: 28_800, ts_ms, kind: FundingKind::Predicted, settle_ms: 28_800_000 }
+    }
+
+    #[tokio::test]
+    async fn publishes_on_deviation_or_heartbeat_and_reports_the_rest() {
+        let policy = PublishPolicy { deviation_bps: Some(10.0), funding_deviation_bps: Some(0.5), heartbeat_ms: Some(60_000) };
+        let p = PolicyPublisher::new("evm", policy, Count::default());
+        for (px, ts) in [(100.0, 0), (100.05, 1_000), (100.2, 2_000), (100.25, 3_000), (100.25, 62_000)] {
+            p.publish_index(IndexTick::fixture("CORN_PERP", px, ts)).await.unwrap();
+        }
+        // First, +20 bps, heartbeat; the +5 bps moves are held back.
+        assert_eq!(p.inner.index.load(Ordering::SeqCst), 3);
+        for (rate, ts) in [(0.0001, 0), (0.000_12, 1_000), (0.0002, 2_000)] {
+            p.publish_funding(funding(rate, ts)).await.unwrap();
+        }
+        p.publish_funding(FundingUpdate { kind: FundingKind::Settled, ..funding(0.0002, 3_000) }).await.unwrap();
+        assert_eq!(p.inner.funding.load(Ordering::SeqCst), 3);
+
+        let kinds: Vec<String> = p.take_suppressed("CORN_PERP").into_iter().map(|s| format!("{}:{}", s.sink, s.kind)).collect();
+        assert_eq!(kinds, ["evm:index", "evm:index", "evm:funding"]);
+        assert!(p.take_suppressed("CORN_PERP").is_empty());
+
+        // No thresholds at all: everything goes out.
+        let all = PolicyPublisher::new("stdout", PublishPolicy::default(), Count::default());
+        all.publish_index(IndexTick::fixture("CORN_PERP", 100.0, 0)).await.unwrap();
+        all.publish_index(IndexTick::fixture("CORN_PERP", 100.0, 1)).await.unwrap();
+        assert_eq!(all.inner.index.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,495 @@
+// src/publishing/solana.rs
+//! Solana publishing path: marks are written into an Anchor-compatible price
+//! account owned by our feed program, one PDA per symbol (`["price", symbol]`).
+//!
+//! Account data (Anchor layout, borsh, little-endian):
+//!   [0..8)   discriminator = sha256("account:PriceAccount")[..8]
+//!   [8..16)  price         i64   (scaled by expo)
+//!   [16..24) conf          u64   (same scale)
+//!   [24..28) expo          i32
+//!   [28..36) publish_time  i64   (unix seconds)
+//!   [36]     status        u8    (PriceStatus)
+//!
+//! The update instruction is `update_price(args)` with Anchor's
+//! `sha256("global:update_price")[..8]` sighash; accounts are the price PDA
+//! (writable) and the authority (signer). Transactions are signed locally and
+//! submitted with `sendTransaction` to the configured RPC URL.
+//!
+//! The oracle publishes a mark before its consensus stats, so a mark waits for
+//! the stats of the same tick and is pushed with their conf; a mark with none
+//! (a frozen republish) goes out once the tick's health arrives, with `Halted`
+//! status if the market is halted and `Unknown` otherwise. Pushes run in the background, one at a time per price account (see
+//! `inflight`): an update arriving meanwhile goes out when that account's push
+//! finishes.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anchor_lang::prelude::{borsh, AccountMeta, AnchorDeserialize, AnchorSerialize, Pubkey};
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::message::Message;
+use anchor_lang::{AccountDeserialize, AccountSerialize, Discriminator, InstructionData, Space};
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey};
+use serde_json::json;
+
+use super::inflight::InFlight;
+use super::policy::Suppressed;
+use super::Publisher;
+use crate::health::{HealthStatus, OracleHealth};
+use crate::types::{scale_by_expo, ConsensusStats, FundingUpdate, IndexTick};
+
+pub const PRICE_SEED: &[u8] = b"price";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub enum PriceStatus {
+    Unknown,
+    Trading,
+    Halted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, AnchorSerialize, AnchorDeserialize)]
+pub struct PriceAccount {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub status: PriceStatus,
+}
+
+// Implemented by hand rather than via `#[account]` so the program id stays a
+// runtime setting instead of a `declare_id!` in this crate.
+impl Discriminator for PriceAccount {
+    const DISCRIMINATOR: &'static [u8] = &[85, 228, 226, 113, 218, 91, 116, 92];
+}
+
+impl Space for PriceAccount {
+    const INIT_SPACE: usize = 8 + 8 + 4 + 8 + 1;
+}
+
+impl AccountSerialize for PriceAccount {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> anchor_lang::Result<()> {
+        writer
+            .write_all(Self::DISCRIMINATOR)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize)?;
+        AnchorSerialize::serialize(self, writer)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize)?;
+        Ok(())
+    }
+}
+
+impl AccountDeserialize for PriceAccount {
+    fn try_deserialize(buf: &mut &[u8]) -> anchor_lang::Result<Self> {
+        if !buf.starts_with(Self::DISCRIMINATOR) {
+            return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_lang::Result<Self> {
+        let mut data: &[u8] = &buf[Self::DISCRIMINATOR.len().min(buf.len())..];
+        AnchorDeserialize::deserialize(&mut data)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+}
+
+impl PriceAccount {
+    /// `conf` is the consensus confidence band (`ConsensusStats::conf`); no stats
+    /// means `Unknown` status and zero conf.
+    pub fn from_tick(tick: &IndexTick, stats: Option<&ConsensusStats>) -> Result<Self> {
+        let scaled = scale_by_expo(tick.price, tick.expo).map_err(|e| anyhow!(e))?;
+        let price = i64::try_from(scaled).map_err(|_| anyhow!("scaled price overflows i64"))?;
+        let (conf, status) = match stats {
+            Some(s) if s.n_used > 0 => (scale_by_expo(s.conf, tick.expo).unwrap_or(0), PriceStatus::Trading),
+            _ => (0, PriceStatus::Unknown),
+        };
+        Ok(Self {
+            price,
+            conf,
+            expo: tick.expo as i32,
+            publish_time: tick.ts_ms.div_euclid(1000),
+            status,
+        })
+    }
+}
+
+/// Args of the `update_price` instruction; same fields as the account.
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize)]
+pub struct UpdatePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub status: PriceStatus,
+}
+
+impl Discriminator for UpdatePrice {
+    const DISCRIMINATOR: &'static [u8] = &[61, 34, 117, 155, 75, 34, 123, 208];
+}
+
+impl InstructionData for UpdatePrice {}
+
+impl From<&PriceAccount> for UpdatePrice {
+    fn from(a: &PriceAccount) -> Self {
+        Self { price: a.price, conf: a.conf, expo: a.expo, publish_time: a.publish_time, status: a.status }
+    }
+}
+
+pub fn price_account_address(program_id: &Pubkey, symbol: &str) -> Pubkey {
+    Pubkey::find_program_address(&[PRICE_SEED, symbol.as_bytes()], program_id).0
+}
+
+pub fn update_price_ix(program_id: &Pubkey, authority: &Pubkey, symbol: &str, acct: &PriceAccount) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(price_account_address(program_id, symbol), false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data: UpdatePrice::from(acct).data(),
+    }
+}
+
+/// Legacy transaction wire format with the authority as sole signer/fee payer.
+pub fn sign_transaction(ix: Instruction, key: &SigningKey, recent_blockhash: Hash) -> Vec<u8> {
+    let payer = Pubkey::new_from_array(key.verifying_key().to_bytes());
+    let mut msg = Message::new(&[ix], Some(&payer));
+    msg.recent_blockhash = recent_blockhash;
+    let msg_bytes = msg.serialize();
+    let sig = key.sign(&msg_bytes);
+
+    let mut out = Vec::with_capacity(1 + 64 + msg_bytes.len());
+    out.push(1); // compact-u16 signature count
+    out.extend_from_slice(&sig.to_bytes());
+    out.extend_from_slice(&msg_bytes);
+    out
+}
+
+#[derive(Debug, Clone)]
+pub struct SolanaConfig {
+    pub rpc_url: String,
+    pub program_id: Pubkey,
+    pub commitment: String,
+}
+
+struct SolanaInner {
+    cfg: SolanaConfig,
+    key: SigningKey,
+    client: reqwest::Client,
+    awaiting_stats: std::sync::Mutex<HashMap<String, IndexTick>>, // this tick's mark, by symbol
+    queue: InFlight<PriceAccount>,
+}
+
+pub struct SolanaPublisher {
+    inner: Arc<SolanaInner>,
+}
+
+impl SolanaPublisher {
+    pub fn new(cfg: SolanaConfig, key: SigningKey) -> Result<Self> {
+        Ok(Self {
+            inner: Arc::new(SolanaInner {
+                cfg,
+                key,
+                client: reqwest::Client::builder().user_agent("autonom-oracle/1.0").build()?,
+                awaiting_stats: std::sync::Mutex::new(HashMap::new()),
+                queue: InFlight::new("solana"),
+            }),
+        })
+    }
+
+    /// Reads `SOLANA_RPC_URL`, `SOLANA_PROGRAM_ID` and `SOLANA_KEYPAIR` (path to a
+    /// solana-cli JSON keypair: 64 bytes, secret then public).
+    pub fn from_env() -> Result<Self> {
+        let rpc_url = std::env::var("SOLANA_RPC_URL").map_err(|_| anyhow!("Set SOLANA_RPC_URL"))?;
+        let program = std::env::var("SOLANA_PROGRAM_ID").map_err(|_| anyhow!("Set SOLANA_PROGRAM_ID"))?;
+        let path = std::env::var("SOLANA_KEYPAIR").map_err(|_| anyhow!("Set SOLANA_KEYPAIR"))?;
+        let raw: Vec<u8> = serde_json::from_str(&std::fs::read_to_string(&path).context("reading SOLANA_KEYPAIR")?)?;
+        let secret: [u8; 32] = raw.get(..32).and_then(|s| s.try_into().ok()).ok_or_else(|| anyhow!("bad keypair file"))?;
+        let cfg = SolanaConfig {
+            rpc_url,
+            program_id: Pubkey::from_str(&program).map_err(|_| anyhow!("invalid SOLANA_PROGRAM_ID"))?,
+            commitment: "confirmed".into(),
+        };
+        Self::new(cfg, SigningKey::from_bytes(&secret))
+    }
+
+    pub fn authority(&self) -> Pubkey {
+        Pubkey::new_from_array(self.inner.key.verifying_key().to_bytes())
+    }
+
+    /// Build, sign and submit one update; returns the transaction signature.
+    pub async fn push(&self, tick: &IndexTick, stats: Option<&ConsensusStats>) -> Result<String> {
+        self.inner.push(&tick.symbol, &PriceAccount::from_tick(tick, stats)?).await
+    }
+
+    /// Push `acct` in the background, or hold it until the account's running push finishes.
+    fn start(&self, symbol: String, acct: PriceAccount) {
+        let Some(acct) = self.inner.queue.offer(&symbol, acct) else {
+            tracing::debug!("solana push for {symbol} in flight, holding the latest update");
+            return;
+        };
+        let inner = self.inner.clone();
+        tokio::spawn(async move {
+            let mut next = Some(acct);
+            while let Some(acct) = next {
+                match inner.push(&symbol, &acct).await {
+                    Ok(sig) => tracing::info!("solana push {} sig={}", symbol, sig),
+                    Err(e) => tracing::warn!("solana push failed for {}: {e:?}", symbol),
+                }
+                next = inner.queue.finish(&symbol);
+            }
+        });
+    }
+}
+
+impl SolanaInner {
+    async fn rpc(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let resp: serde_json::Value = self
+            .client
+            .post(&self.cfg.rpc_url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("decoding {method} response"))?;
+        if let Some(err) = resp.get("error") {
+            return Err(anyhow!("{method} failed: {err}"));
+        }
+        resp.get("result").cloned().ok_or_else(|| anyhow!("{method}: missing result"))
+    }
+
+    async fn push(&self, symbol: &str, acct: &PriceAccount) -> Result<String> {
+        let authority = Pubkey::new_from_array(self.key.verifying_key().to_bytes());
+        let ix = update_price_ix(&self.cfg.program_id, &authority, symbol, acct);
+
+        let bh = self
+            .rpc("getLatestBlockhash", json!([{ "commitment": self.cfg.commitment }]))
+            .await?;
+        let bh = bh["value"]["blockhash"].as_str().ok_or_else(|| anyhow!("getLatestBlockhash: no blockhash"))?;
+        let blockhash = Hash::from_str(bh).map_err(|_| anyhow!("bad blockhash {bh}"))?;
+
+        let tx = sign_transaction(ix, &self.key, blockhash);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(tx);
+        let sig = self
+            .rpc(
+                "sendTransaction",
+                json!([encoded, { "encoding": "base64", "preflightCommitment": self.cfg.commitment }]),
+            )
+            .await?;
+        sig.as_str().map(str::to_string).ok_or_else(|| anyhow!("sendTransaction: no signature"))
+    }
+}
+
+#[async_trait::async_trait]
+impl Publisher for SolanaPublisher {
+    /// Held until the same tick's consensus stats (or health) arrive.
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        let replaced = self.inner.awaiting_stats.lock().unwrap().insert(tick.symbol.clone(), tick);
+        if let Some(unpaired) = replaced {
+            self.start(unpaired.symbol.clone(), PriceAccount::from_tick(&unpaired, None)?);
+        }
+        Ok(())
+    }
+
+    async fn publish_consensus(&self, symbol: &str, stats: ConsensusStats) -> anyhow::Result<()> {
+        let tick = self.inner.awaiting_stats.lock().unwrap().remove(symbol);
+        if let Some(tick) = tick {
+            self.start(tick.symbol.clone(), PriceAccount::from_tick(&tick, Some(&stats))?);
+        }
+        Ok(())
+    }
+
+    /// A mark still waiting at the end of its tick had no stats; if the market
+    /// is halted it is the frozen last good mark.
+    async fn publish_health(&self, health: OracleHealth) -> anyhow::Result<()> {
+        let tick = self.inner.awaiting_stats.lock().unwrap().remove(&health.symbol);
+        if let Some(tick) = tick {
+            let mut acct = PriceAccount::from_tick(&tick, None)?;
+            if health.status == HealthStatus::Halted {
+                acct.status = PriceStatus::Halted;
+            }
+            self.start(tick.symbol, acct);
+        }
+        Ok(())
+    }
+
+    /// Funding is not mirrored on-chain.
+    async fn publish_funding(&self, _fu: FundingUpdate) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn take_suppressed(&self, symbol: &str) -> Vec<Suppressed> {
+        self.inner.queue.take_skipped(symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier};
+    use httpmock::{Method::POST, MockServer};
+    use sha2::{Digest, Sha256};
+
+    fn tick() -> IndexTick {
+        IndexTick::fixture("GOLD_PERP", 2_400.5, 1_700_000_123_456)
+    }
+
+    fn stats() -> ConsensusStats {
+        ConsensusStats { n_fresh: 3, n_used: 3, n_dropped: 0, spread_bps: 10, confidence: 0.8, conf: 1.20025 }
+    }
+
+    #[test]
+    fn discriminators_match_anchor() {
+        assert_eq!(PriceAccount::DISCRIMINATOR, &Sha256::digest(b"account:PriceAccount")[..8]);
+        assert_eq!(UpdatePrice::DISCRIMINATOR, &Sha256::digest(b"global:update_price")[..8]);
+    }
+
+    #[test]
+    fn account_byte_layout() {
+        let acct = PriceAccount::from_tick(&tick(), Some(&stats())).unwrap();
+        let mut buf = Vec::new();
+        acct.try_serialize(&mut buf).unwrap();
+        assert_eq!(buf.len(), 8 + PriceAccount::INIT_SPACE);
+        assert_eq!(&buf[..8], PriceAccount::DISCRIMINATOR);
+        assert_eq!(i64::from_le_bytes(buf[8..16].try_into().unwrap()), 240_050_000_000);
+        assert_eq!(u64::from_le_bytes(buf[16..24].try_into().unwrap()), 120_025_000); // stats.conf at expo -8
+        assert_eq!(i32::from_le_bytes(buf[24..28].try_into().unwrap()), -8);
+        assert_eq!(i64::from_le_bytes(buf[28..36].try_into().unwrap()), 1_700_000_123);
+        assert_eq!(buf[36], 1); // Trading
+
+        let back = PriceAccount::try_deserialize(&mut buf.as_slice()).unwrap();
+        assert_eq!(back, acct);
+        buf[0] ^= 1;
+        assert!(PriceAccount::try_deserialize(&mut buf.as_slice()).is_err());
+    }
+
+    #[tokio::test]
+    async fn submits_signed_update_to_rpc() {
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let program_id = Pubkey::new_from_array([9; 32]);
+        let blockhash = Hash::new_from_array([7; 32]);
+
+        // Signing is deterministic, so we know exactly which bytes must be sent.
+        let authority = Pubkey::new_from_array(key.verifying_key().to_bytes());
+        let acct = PriceAccount::from_tick(&tick(), Some(&stats())).unwrap();
+        let ix = update_price_ix(&program_id, &authority, "GOLD_PERP", &acct);
+        assert_eq!(ix.accounts[0].pubkey, price_account_address(&program_id, "GOLD_PERP"));
+        let tx = sign_transaction(ix, &key, blockhash);
+        assert_eq!(tx[0], 1);
+        let sig = Signature::from_slice(&tx[1..65]).unwrap();
+        key.verifying_key().verify(&tx[65..], &sig).unwrap();
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&tx);
+
+        let server = MockServer::start_async().await;
+        server.mock_async(|when, then| {
+            when.method(POST).body_contains("\"getLatestBlockhash\"");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0", "id": 1,
+                "result": { "context": { "slot": 1 }, "value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 } }
+            }));
+        }).await;
+        let send = server.mock_async(|when, then| {
+            when.method(POST).body_contains("\"sendTransaction\"").body_contains(encoded.as_str());
+            then.status(200).json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "5igSig" }));
+        }).await;
+
+        let cfg = SolanaConfig { rpc_url: server.base_url(), program_id, commitment: "confirmed".into() };
+        let p = SolanaPublisher::new(cfg, key).unwrap();
+        assert_eq!(p.authority(), authority);
+        assert_eq!(p.push(&tick(), Some(&stats())).await.unwrap(), "5igSig");
+        send.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn pushes_a_mark_with_the_same_ticks_stats() {
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let program_id = Pubkey::new_from_array([9; 32]);
+        let blockhash = Hash::new_from_array([7; 32]);
+        let authority = Pubkey::new_from_array(key.verifying_key().to_bytes());
+        let acct = PriceAccount::from_tick(&tick(), Some(&stats())).unwrap();
+        let tx = sign_transaction(update_price_ix(&program_id, &authority, "GOLD_PERP", &acct), &key, blockhash);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&tx);
+
+        let server = MockServer::start_async().await;
+        server.mock_async(|when, then| {
+            when.method(POST).body_contains("\"getLatestBlockhash\"");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0", "id": 1,
+                "result": { "context": { "slot": 1 }, "value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 } }
+            }));
+        }).await;
+        let paired = server.mock_async(|when, then| {
+            when.method(POST).body_contains("\"sendTransaction\"").body_contains(encoded.as_str());
+            then.status(200).json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "5igSig" }));
+        }).await;
+        let other = server.mock_async(|when, then| {
+            when.method(POST).body_contains("\"sendTransaction\"");
+            then.status(200).json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "5igSig" }));
+        }).await;
+
+        let cfg = SolanaConfig { rpc_url: server.base_url(), program_id, commitment: "confirmed".into() };
+        let p = SolanaPublisher::new(cfg, key).unwrap();
+        // The previous tick's stats must not be used for this mark.
+        let stale = ConsensusStats { conf: 9.0, ..stats() };
+        p.publish_consensus("GOLD_PERP", stale).await.unwrap();
+        p.publish_index(tick()).await.unwrap();
+        p.publish_consensus("GOLD_PERP", stats()).await.unwrap();
+        for _ in 0..100 {
+            if paired.hits_async().await > 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!((paired.hits_async().await, other.hits_async().await), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn pushes_a_frozen_mark_as_halted() {
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let program_id = Pubkey::new_from_array([9; 32]);
+        let blockhash = Hash::new_from_array([7; 32]);
+        let authority = Pubkey::new_from_array(key.verifying_key().to_bytes());
+        let acct = PriceAccount { status: PriceStatus::Halted, ..PriceAccount::from_tick(&tick(), None).unwrap() };
+        let tx = sign_transaction(update_price_ix(&program_id, &authority, "GOLD_PERP", &acct), &key, blockhash);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&tx);
+
+        let server = MockServer::start_async().await;
+        server.mock_async(|when, then| {
+            when.method(POST).body_contains("\"getLatestBlockhash\"");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0", "id": 1,
+                "result": { "context": { "slot": 1 }, "value": { "blockhash": blockhash.to_string(), "lastValidBlockHeight": 100 } }
+            }));
+        }).await;
+        let halted = server.mock_async(|when, then| {
+            when.method(POST).body_contains("\"sendTransaction\"").body_contains(encoded.as_str());
+            then.status(200).json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "5igSig" }));
+        }).await;
+
+        let cfg = SolanaConfig { rpc_url: server.base_url(), program_id, commitment: "confirmed".into() };
+        let p = SolanaPublisher::new(cfg, key).unwrap();
+        p.publish_index(tick()).await.unwrap();
+        let health = OracleHealth {
+            symbol: "GOLD_PERP".into(),
+            status: HealthStatus::Halted,
+            reasons: vec![],
+            since_ms: 0,
+            last_mark_ms: None,
+            ts_ms: 0,
+        };
+        p.publish_health(health).await.unwrap();
+        for _ in 0..100 {
+            if halted.hits_async().await > 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(halted.hits_async().await, 1);
+    }
+}
@@ -0,0 +1,298 @@
+// src/publishing/sqlite.rs
+//! SQLite history sink: every mark, funding settlement and raw CFD quote is appended,
+//! and the query helpers below read it back (range by symbol/time, latest N).
+//! Schema lives in `migrations/` and is embedded at compile time.
+
+use std::str::FromStr;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+
+use super::Publisher;
+use crate::clock::{self, SharedClock};
+use crate::signing::SignedIndexTick;
+use crate::types::{scale_by_expo, CfdQuote, FundingKind, FundingUpdate, IndexTick};
+
+pub static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TickRow {
+    pub symbol: String,
+    pub price: f64,
+    pub price_scaled: Option<i64>,
+    pub expo: i64,
+    pub ts_ms: i64,
+    pub source: String,
+    pub window_sec: i64,
+    pub signature: Option<String>,
+    pub recovery_id: Option<i64>,
+    pub signer: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct FundingRow {
+    pub symbol: String,
+    pub rate: f64,
+    pub interval_sec: i64,
+    pub ts_ms: i64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct QuoteRow {
+    pub symbol: String,
+    pub provider: String,
+    pub price: f64,
+    pub ts_ms: i64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct SqlitePublisher {
+    pool: SqlitePool,
+    clock: SharedClock, // stamps `recorded_at`
+}
+
+impl SqlitePublisher {
+    /// Open (creating if missing) and migrate, e.g. `sqlite://var/history.db`.
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        let opts = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+        // An in-memory database only exists per connection, so keep exactly one.
+        let max_conns = if url.contains(":memory:") { 1 } else { 4 };
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_conns)
+            .connect_with(opts)
+            .await?;
+        Self::from_pool(pool).await
+    }
+
+    pub async fn from_pool(pool: SqlitePool) -> anyhow::Result<Self> {
+        MIGRATOR.run(&pool).await?;
+        Ok(Self { pool, clock: clock::system() })
+    }
+
+    /// Stamp `recorded_at` from `clock` instead of the wall clock (replays, tests).
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn pool(&self) -> &SqlitePool { &self.pool }
+
+    async fn insert_tick(&self, tick: &IndexTick, signed: Option<&SignedIndexTick>) -> anyhow::Result<()> {
+        // NULL for an expo `scale_by_expo` doesn't support; a price past i64 is an error.
+        let price_scaled = match scale_by_expo(tick.price, tick.expo) {
+            Ok(v) => Some(i64::try_from(v).context("scaled price overflows i64")?),
+            Err(_) => None,
+        };
+        sqlx::query(
+            "INSERT INTO index_ticks \
+             (symbol, price, price_scaled, expo, ts_ms, source, window_sec, signature, recovery_id, signer, recorded_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&tick.symbol)
+        .bind(tick.price)
+        .bind(price_scaled)
+        .bind(tick.expo as i64)
+        .bind(tick.ts_ms)
+        .bind(tick.source)
+        .bind(tick.window_sec as i64)
+        .bind(signed.map(|s| s.signature.as_str()))
+        .bind(signed.map(|s| s.recovery_id as i64))
+        .bind(signed.map(|s| s.signer.as_str()))
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // ---- queries ----
+
+    /// Ticks for `symbol` with `from_ms <= ts_ms <= to_ms`, oldest first.
+    pub async fn ticks_range(&self, symbol: &str, from_ms: i64, to_ms: i64) -> anyhow::Result<Vec<TickRow>> {
+        Ok(sqlx::query_as::<_, TickRow>(
+            "SELECT symbol, price, price_scaled, expo, ts_ms, source, window_sec, signature, recovery_id, signer, recorded_at \
+             FROM index_ticks WHERE symbol = ? AND ts_ms BETWEEN ? AND ? ORDER BY ts_ms ASC, id ASC",
+        )
+        .bind(symbol)
+        .bind(from_ms)
+        .bind(to_ms)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Most recent `n` ticks for `symbol`, newest first.
+    pub async fn latest_ticks(&self, symbol: &str, n: u32) -> anyhow::Result<Vec<TickRow>> {
+        Ok(sqlx::query_as::<_, TickRow>(
+            "SELECT symbol, price, price_scaled, expo, ts_ms, source, window_sec, signature, recovery_id, signer, recorded_at \
+             FROM index_ticks WHERE symbol = ? ORDER BY ts_ms DESC, id DESC LIMIT ?",
+        )
+        .bind(symbol)
+        .bind(n as i64)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Funding updates by published symbol (e.g. `LEAN_HOGS_PERP-PERP`), oldest first.
+    pub async fn funding_range(&self, symbol: &str, from_ms: i64, to_ms: i64) -> anyhow::Result<Vec<FundingRow>> {
+        Ok(sqlx::query_as::<_, FundingRow>(
+            "SELECT symbol, rate, interval_sec, ts_ms, recorded_at \
+             FROM funding_updates WHERE symbol = ? AND ts_ms BETWEEN ? AND ? ORDER BY ts_ms ASC, id ASC",
+        )
+        .bind(symbol)
+        .bind(from_ms)
+        .bind(to_ms)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    pub async fn latest_funding(&self, symbol: &str, n: u32) -> anyhow::Result<Vec<FundingRow>> {
+        Ok(sqlx::query_as::<_, FundingRow>(
+            "SELECT symbol, rate, interval_sec, ts_ms, recorded_at \
+             FROM funding_updates WHERE symbol = ? ORDER BY ts_ms DESC, id DESC LIMIT ?",
+        )
+        .bind(symbol)
+        .bind(n as i64)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Raw quotes for `symbol`, optionally narrowed to one provider, oldest first.
+    pub async fn quotes_range(
+        &self,
+        symbol: &str,
+        provider: Option<&str>,
+        from_ms: i64,
+        to_ms: i64,
+    ) -> anyhow::Result<Vec<QuoteRow>> {
+        Ok(sqlx::query_as::<_, QuoteRow>(
+            "SELECT symbol, provider, price, ts_ms, recorded_at FROM cfd_quotes \
+             WHERE symbol = ? AND (? IS NULL OR provider = ?) AND ts_ms BETWEEN ? AND ? \
+             ORDER BY ts_ms ASC, id ASC",
+        )
+        .bind(symbol)
+        .bind(provider)
+        .bind(provider)
+        .bind(from_ms)
+        .bind(to_ms)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    pub async fn latest_quotes(&self, symbol: &str, n: u32) -> anyhow::Result<Vec<QuoteRow>> {
+        Ok(sqlx::query_as::<_, QuoteRow>(
+            "SELECT symbol, provider, price, ts_ms, recorded_at FROM cfd_quotes \
+             WHERE symbol = ? ORDER BY ts_ms DESC, id DESC LIMIT ?",
+        )
+        .bind(symbol)
+        .bind(n as i64)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+}
+
+#[async_trait::async_trait]
+impl Publisher for SqlitePublisher {
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        self.insert_tick(&tick, None).await
+    }
+
+    async fn publish_signed(&self, signed: SignedIndexTick) -> anyhow::Result<()> {
+        self.insert_tick(&signed.tick, Some(&signed)).await
+    }
+
+    /// Only settlements are history; the per-tick predictions are not kept.
+    async fn publish_funding(&self, fu: FundingUpdate) -> anyhow::Result<()> {
+        if fu.kind == FundingKind::Predicted {
+            return Ok(());
+        }
+        sqlx::query(
+            "INSERT INTO funding_updates (symbol, rate, interval_sec, ts_ms, recorded_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&fu.symbol)
+        .bind(fu.rate)
+        .bind(fu.interval_sec as i64)
+        .bind(fu.ts_ms)
+        .bind(self.clock.now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn publish_quotes(&self, symbol: &str, quotes: &[CfdQuote]) -> anyhow::Result<()> {
+        let now = self.clock.now();
+        let mut tx = self.pool.begin().await?;
+        for q in quotes {
+            sqlx::query(
+                "INSERT INTO cfd_quotes (symbol, provider, price, ts_ms, recorded_at) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(symbol)
+            .bind(q.src.name())
+            .bind(q.price)
+            .bind(q.ts_ms)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::types::CfdSource;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn stores_and_queries_history() {
+        let clock = ManualClock::new(1_700_000_000_000);
+        let db = SqlitePublisher::connect("sqlite::memory:").await.unwrap().with_clock(Arc::new(clock.clone()));
+        for (i, px) in [4.10, 4.11, 4.12].into_iter().enumerate() {
+            db.publish_index(IndexTick::fixture("CORN_PERP", px, 1_000 + i as i64 * 1_000)).await.unwrap();
+        }
+        let settled = FundingUpdate {
+            symbol: "CORN_PERP-PERP".into(),
+            rate: 0.0002,
+            interval_sec: 28_800,
+            ts_ms: 3_000,
+            kind: FundingKind::Settled,
+            settle_ms: 0,
+        };
+        db.publish_funding(FundingUpdate { kind: FundingKind::Predicted, ..settled.clone() }).await.unwrap();
+        db.publish_funding(settled).await.unwrap();
+        db.publish_quotes("CORN_PERP", &[
+            CfdQuote { src: CfdSource::Ninjas, price: 4.12, ts_ms: 2_900 },
+            CfdQuote { src: CfdSource::Owninja, price: 4.13, ts_ms: 2_950 },
+        ])
+        .await
+        .unwrap();
+
+        let range = db.ticks_range("CORN_PERP", 1_500, 3_000).await.unwrap();
+        assert_eq!(range.iter().map(|r| r.ts_ms).collect::<Vec<_>>(), vec![2_000, 3_000]);
+        assert_eq!(range[1].price_scaled, Some(412_000_000));
+
+        let latest = db.latest_ticks("CORN_PERP", 1).await.unwrap();
+        assert_eq!(latest[0].price, 4.12);
+
+        assert_eq!(latest[0].recorded_at.timestamp_millis(), 1_700_000_000_000);
+
+        let funding = db.latest_funding("CORN_PERP-PERP", 5).await.unwrap();
+        assert_eq!(funding.len(), 1);
+        assert_eq!(funding[0].recorded_at.timestamp_millis(), 1_700_000_000_000);
+        let ninjas = db.quotes_range("CORN_PERP", Some("ninjas"), 0, i64::MAX).await.unwrap();
+        assert_eq!(ninjas.len(), 1);
+        assert_eq!(db.latest_quotes("CORN_PERP", 10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_a_price_that_overflows_the_scaled_column() {
+        let db = SqlitePublisher::connect("sqlite::memory:").await.unwrap();
+        assert!(db.publish_index(IndexTick::fixture("CORN_PERP", 1e12, 1_000)).await.is_err());
+        assert!(db.latest_ticks("CORN_PERP", 1).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,198 @@
+// src/publishing/web2_cache.rs
+//! `[publish] mode = "web2_cache"`: keep a JSON snapshot of the latest mark and
+//! funding per symbol at `web2_cache_path` for the web2 server to read.
+//!
+//! Updates are collected in memory and the file is rewritten once per tick, when
+//! the tick's health (the last thing every tick publishes) arrives. Every write
+//! goes to a temp file in the same directory and is renamed over the target, so
+//! readers only ever see a complete document. `seq` and `written_ms` let readers
+//! detect a stalled oracle.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use super::Publisher;
+use crate::health::OracleHealth;
+use crate::types::{ConsensusStats, FundingKind, FundingUpdate, IndexTick, PriceFeed};
+
+pub const CACHE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CachedMarket {
+    pub mark: Option<serde_json::Value>,     // IndexTick as JSON (its &'static source can't be read back)
+    pub mark_seq: u64,                       // bumps on every mark for this symbol
+    pub stats: Option<ConsensusStats>,
+    pub price_feed: Option<PriceFeed>,
+    pub health: Option<OracleHealth>,       // readers should check `health.status` before trusting `mark`
+    pub funding: Option<FundingUpdate>,     // last settlement
+    pub funding_seq: u64,                   // bumps on every settlement
+    #[serde(default)]
+    pub predicted_funding: Option<FundingUpdate>, // for the next settlement
+    pub updated_ms: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Web2Cache {
+    pub version: u32,
+    pub seq: u64,          // bumps on every write of the file
+    pub written_ms: i64,   // wall clock at write time
+    pub markets: BTreeMap<String, CachedMarket>,
+}
+
+pub struct Web2CachePublisher {
+    path: PathBuf,
+    state: Mutex<Web2Cache>,
+}
+
+impl Web2CachePublisher {
+    /// Starts from the existing file when it parses, so sequence numbers keep
+    /// increasing across restarts.
+    pub async fn new<P: Into<PathBuf>>(path: P) -> anyhow::Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let state = match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice::<Web2Cache>(&bytes).unwrap_or_default(),
+            Err(_) => Web2Cache::default(),
+        };
+        Ok(Self { path, state: Mutex::new(state) })
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+
+    /// Apply `f` to `symbol`'s entry in memory; with `write`, also rewrite the file.
+    async fn update<F: FnOnce(&mut CachedMarket)>(&self, symbol: &str, write: bool, f: F) -> anyhow::Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        // Held across the write so concurrent updates never rename out of order.
+        let mut state = self.state.lock().await;
+        let m = state.markets.entry(symbol.to_string()).or_default();
+        f(m);
+        m.updated_ms = now;
+        if !write {
+            return Ok(());
+        }
+        state.version = CACHE_VERSION;
+        state.seq += 1;
+        state.written_ms = now;
+        let bytes = serde_json::to_vec(&*state)?;
+        write_atomic(&self.path, &bytes).await
+    }
+}
+
+/// Write to `<path>.tmp`, fsync, then rename over `path`.
+pub async fn write_atomic(path: &Path, bytes: &[u8]) -> anyhow::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut f = tokio::fs::File::create(&tmp).await?;
+    f.write_all(bytes).await?;
+    f.sync_all().await?;
+    drop(f);
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl Publisher for Web2CachePublisher {
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        let v = serde_json::to_value(&tick)?;
+        self.update(&tick.symbol, false, |m| {
+            m.mark = Some(v);
+            m.mark_seq += 1;
+        })
+        .await
+    }
+    async fn publish_funding(&self, fu: FundingUpdate) -> anyhow::Result<()> {
+        let symbol = crate::server::market_symbol(&fu.symbol).to_string();
+        self.update(&symbol, false, |m| match fu.kind {
+            FundingKind::Settled => {
+                m.funding = Some(fu);
+                m.funding_seq += 1;
+            }
+            FundingKind::Predicted => m.predicted_funding = Some(fu),
+        })
+        .await
+    }
+    async fn publish_consensus(&self, symbol: &str, stats: ConsensusStats) -> anyhow::Result<()> {
+        self.update(symbol, false, |m| m.stats = Some(stats)).await
+    }
+    async fn publish_price_feed(&self, feed: PriceFeed) -> anyhow::Result<()> {
+        let symbol = feed.symbol.clone();
+        self.update(&symbol, false, |m| m.price_feed = Some(feed)).await
+    }
+    /// Sent last every tick, so this is where the tick's updates hit the file.
+    async fn publish_health(&self, health: OracleHealth) -> anyhow::Result<()> {
+        let symbol = health.symbol.clone();
+        self.update(&symbol, true, |m| m.health = Some(health)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::health::HealthStatus;
+
+    fn health() -> OracleHealth {
+        OracleHealth {
+            symbol: "COCOA_PERP".into(),
+            status: HealthStatus::Healthy,
+            reasons: vec![],
+            since_ms: 0,
+            last_mark_ms: None,
+            ts_ms: 1_700_000_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn writes_snapshot_and_resumes_sequence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache/web2_cache.json");
+        let p = Web2CachePublisher::new(&path).await.unwrap();
+        p.publish_index(IndexTick {
+            symbol: "COCOA_PERP".into(),
+            price: 8_123.5,
+            expo: -8,
+            ts_ms: 1_700_000_000_000,
+            source: "cfd-consensus",
+            window_sec: 0,
+        })
+        .await
+        .unwrap();
+        p.publish_funding(FundingUpdate {
+            symbol: "COCOA_PERP-PERP".into(),
+            rate: -0.0001,
+            interval_sec: 28_800,
+            ts_ms: 1,
+            kind: FundingKind::Settled,
+            settle_ms: 0,
+        })
+        .await
+        .unwrap();
+        assert!(!path.exists()); // nothing is written until the tick's health
+        p.publish_health(health()).await.unwrap();
+
+        let cache: Web2Cache = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(cache.seq, 1);
+        let m = &cache.markets["COCOA_PERP"];
+        assert_eq!(m.mark_seq, 1);
+        assert_eq!(m.funding_seq, 1);
+        assert_eq!(m.mark.as_ref().unwrap()["price"], 8_123.5);
+        assert!(!path.with_extension("json.tmp").exists());
+
+        let reopened = Web2CachePublisher::new(&path).await.unwrap();
+        reopened.publish_consensus("COCOA_PERP", ConsensusStats {
+            n_fresh: 2, n_used: 2, n_dropped: 0, spread_bps: 3, confidence: 0.9, conf: 0.5,
+        })
+        .await
+        .unwrap();
+        reopened.publish_health(health()).await.unwrap();
+        let cache: Web2Cache = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(cache.seq, 2);
+        assert!(cache.markets["COCOA_PERP"].stats.is_some());
+    }
+}
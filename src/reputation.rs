@@ -0,0 +1,214 @@
+// src/reputation.rs
+//! Per-provider reputation for one market.
+//!
+//! Every tick each polled provider contributes one observation, read off the
+//! tick's `TickReport`: an error (failure, timeout or invalid quote), a stale
+//! quote, or a fresh quote scored against the consensus median (deviation in
+//! bps, and whether it fell outside the MAD band). Each component is an EWMA
+//! over roughly `window` observations, starting from a clean record, and
+//!
+//!   score = (1 − deviation/deviation_bps_scale)·(1 − outlier rate)·(1 − stale rate)·(1 − error rate)
+//!
+//! so one consistently bad dimension is enough to sink it. A provider whose
+//! score drops below `quarantine_below` (after `min_samples`) is quarantined:
+//! still polled and scored, but its quotes stay out of the consensus. Once
+//! `probation_ms` has passed and its score is back at `reinstate_above` it is
+//! reinstated. A provider is never quarantined if that would leave fewer
+//! active providers than the market needs fresh quotes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::audit::{QuoteVerdict, TickReport};
+use crate::config::ReputationConfig;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderScore {
+    pub provider: String,
+    pub samples: u32,
+    pub deviation_bps: f64, // EWMA of |quote − median| in bps
+    pub outlier_rate: f64,
+    pub stale_rate: f64,
+    pub error_rate: f64,
+    pub score: f64,
+    pub quarantined_since_ms: Option<i64>,
+}
+
+impl ProviderScore {
+    fn new(provider: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            samples: 0,
+            deviation_bps: 0.0,
+            outlier_rate: 0.0,
+            stale_rate: 0.0,
+            error_rate: 0.0,
+            score: 1.0,
+            quarantined_since_ms: None,
+        }
+    }
+
+    pub fn quarantined(&self) -> bool { self.quarantined_since_ms.is_some() }
+}
+
+/// What one tick said about one provider.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Observation {
+    Error,
+    Stale,
+    Quote { deviation_bps: f64, outlier: bool },
+    Unscored, // fresh, but the tick had no consensus to compare against
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReputationEventKind {
+    Quarantined,
+    Reinstated,
+}
+
+/// A quarantine state change, recorded in the tick report it happened in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReputationEvent {
+    pub provider: String,
+    pub kind: ReputationEventKind,
+    pub score: f64,
+    pub ts_ms: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReputationTracker {
+    pub cfg: ReputationConfig,
+    providers: Vec<ProviderScore>,
+}
+
+impl ReputationTracker {
+    pub fn new(cfg: ReputationConfig) -> Self {
+        Self { cfg, providers: Vec::new() }
+    }
+
+    pub fn scores(&self) -> &[ProviderScore] { &self.providers }
+
+    /// Pick up scores and quarantines saved by a previous run.
+    pub fn restore(&mut self, scores: Vec<ProviderScore>) {
+        self.providers = scores;
+    }
+
+    pub fn is_quarantined(&self, provider: &str) -> bool {
+        self.providers.iter().any(|p| p.provider == provider && p.quarantined())
+    }
+
+    pub fn observe(&mut self, provider: &str, obs: Observation) {
+        let alpha = 2.0 / (self.cfg.window.max(1) as f64 + 1.0);
+        let ewma = |v: &mut f64, x: f64| *v += alpha * (x - *v);
+        let scale = self.cfg.deviation_bps_scale;
+        let p = match self.providers.iter().position(|p| p.provider == provider) {
+            Some(i) => &mut self.providers[i],
+            None => {
+                self.providers.push(ProviderScore::new(provider));
+                self.providers.last_mut().unwrap()
+            }
+        };
+        p.samples = p.samples.saturating_add(1);
+        ewma(&mut p.error_rate, (obs == Observation::Error) as u8 as f64);
+        if obs != Observation::Error {
+            ewma(&mut p.stale_rate, (obs == Observation::Stale) as u8 as f64);
+        }
+        if let Observation::Quote { deviation_bps, outlier } = obs {
+            ewma(&mut p.deviation_bps, deviation_bps);
+            ewma(&mut p.outlier_rate, outlier as u8 as f64);
+        }
+        let dev = (p.deviation_bps / scale).min(1.0);
+        p.score = (1.0 - dev) * (1.0 - p.outlier_rate) * (1.0 - p.stale_rate) * (1.0 - p.error_rate);
+    }
+
+    /// Score every provider that took part in `report`. Late quotes were already
+    /// counted as a timeout on the tick they missed.
+    pub fn observe_report(&mut self, report: &TickReport) {
+        let consensus = report.consensus.as_ref().map(|c| (c.median, c.band));
+        for q in &report.quotes {
+            let obs = match q.verdict {
+                QuoteVerdict::Late => continue,
+                QuoteVerdict::Invalid => Observation::Error,
+                QuoteVerdict::Stale => Observation::Stale,
+                QuoteVerdict::Unused => Observation::Unscored,
+                QuoteVerdict::Kept | QuoteVerdict::Outlier | QuoteVerdict::Quarantined => match consensus {
+                    // Same test as the consensus filter, so quarantined quotes are judged alike.
+                    Some((median, band)) => Observation::Quote {
+                        deviation_bps: ((q.price - median) / median).abs() * 10_000.0,
+                        outlier: (q.price - median).abs() > band,
+                    },
+                    None => Observation::Unscored,
+                },
+            };
+            self.observe(&q.provider, obs);
+        }
+        for f in &report.failures {
+            self.observe(&f.provider, Observation::Error);
+        }
+    }
+
+    /// Quarantine or reinstate providers based on their current scores. At least
+    /// `min_active` providers are always left active.
+    pub fn evaluate(&mut self, now_ms: i64, min_active: usize) -> Vec<ReputationEvent> {
+        let mut events = Vec::new();
+        let mut active = self.providers.iter().filter(|p| !p.quarantined()).count();
+        let cfg = &self.cfg;
+        for p in &mut self.providers {
+            let event = match p.quarantined_since_ms {
+                Some(since) if now_ms - since >= cfg.probation_ms as i64 && p.score >= cfg.reinstate_above => {
+                    p.quarantined_since_ms = None;
+                    active += 1;
+                    ReputationEventKind::Reinstated
+                }
+                None if cfg.enabled && p.samples >= cfg.min_samples && p.score < cfg.quarantine_below && active > min_active => {
+                    p.quarantined_since_ms = Some(now_ms);
+                    active -= 1;
+                    ReputationEventKind::Quarantined
+                }
+                _ => continue,
+            };
+            events.push(ReputationEvent { provider: p.provider.clone(), kind: event, score: p.score, ts_ms: now_ms });
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarantines_bad_providers_and_reinstates_after_probation() {
+        let cfg = ReputationConfig { window: 4, min_samples: 3, probation_ms: 10_000, ..ReputationConfig::default() };
+        let mut r = ReputationTracker::new(cfg);
+        let good = Observation::Quote { deviation_bps: 2.0, outlier: false };
+        let kinds = |events: Vec<ReputationEvent>| events.into_iter().map(|e| (e.provider, e.kind)).collect::<Vec<_>>();
+        for t in 0..3 {
+            r.observe("a", good);
+            r.observe("b", good);
+            r.observe("c", Observation::Error);
+            r.observe("d", Observation::Error);
+            let events = kinds(r.evaluate(t * 1_000, 3));
+            if t < 2 {
+                assert!(events.is_empty(), "before min_samples: {events:?}");
+            } else {
+                // Quarantining d as well would leave fewer than three active.
+                assert_eq!(events, [("c".to_string(), ReputationEventKind::Quarantined)]);
+            }
+        }
+        assert!(r.is_quarantined("c") && !r.is_quarantined("d"));
+        assert!(r.scores()[2].error_rate > 0.7);
+
+        // c recovers quickly, but stays out until probation has passed.
+        for _ in 0..6 {
+            r.observe("c", good);
+        }
+        assert!(r.scores()[2].score >= 0.7);
+        assert!(r.evaluate(5_000, 3).is_empty());
+        assert_eq!(kinds(r.evaluate(12_000, 3)), [
+            ("c".to_string(), ReputationEventKind::Reinstated),
+            ("d".to_string(), ReputationEventKind::Quarantined),
+        ]);
+        assert!(!r.is_quarantined("c") && r.is_quarantined("d"));
+    }
+}
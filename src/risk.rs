@@ -1,4 +1,11 @@
 // src/risk.rs
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audit::{BreakerEvent, BreakerReport, WindowReport};
+use crate::clock::{self, SharedClock};
+use crate::config::{BreakerWindow, OracleConfig};
 use crate::index::IndexError;
 use chrono::{Datelike, Timelike};
 
@@ -9,43 +16,144 @@ pub struct RiskSwitches {
     pub hours_open: bool,
 }
 
+/// Breaker limits: the largest move allowed within each window, how long a trip
+/// holds at least, and how long the price must stay settled before it resets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakerConfig {
+    pub windows: Vec<BreakerWindow>,
+    pub cooldown_ms: u64,
+    pub settle_ms: u64,
+}
+
+impl From<&OracleConfig> for BreakerConfig {
+    /// The 1-minute `circuit_breaker_per_min` plus any `circuit_breaker_windows`.
+    fn from(cfg: &OracleConfig) -> Self {
+        let one_min = BreakerWindow { secs: 60, max_move: cfg.circuit_breaker_per_min };
+        let mut windows: Vec<BreakerWindow> = std::iter::once(one_min).chain(cfg.circuit_breaker_windows.iter().copied()).collect();
+        windows.sort_by_key(|w| w.secs);
+        Self { windows, cooldown_ms: cfg.circuit_breaker_cooldown_ms, settle_ms: cfg.circuit_breaker_settle_ms }
+    }
+}
+
+/// Multi-window circuit breaker.
+///
+/// Every candidate mark is recorded. The breaker trips when the newest price is
+/// further than `max_move` (as a fraction) from any price seen within one of the
+/// windows. A trip holds for at least `cooldown_ms`; after that it resets once
+/// the prices since the trip over the last `settle_ms` (and the one before,
+/// which held into that span) are within the shortest window's `max_move` of
+/// each other. A reset forgets the history, so the settled level becomes the
+/// new reference for every window.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    pub cfg: BreakerConfig,
+    history: VecDeque<(i64, f64)>, // (ts_ms, price), oldest first, pruned to the longest window
+    tripped_since_ms: Option<i64>,
+}
+
+impl CircuitBreaker {
+    pub fn new(cfg: BreakerConfig) -> Self {
+        Self { cfg, history: VecDeque::new(), tripped_since_ms: None }
+    }
+
+    pub fn tripped_since_ms(&self) -> Option<i64> { self.tripped_since_ms }
+
+    /// Record `px` at `ts_ms` and decide whether the mark may be published.
+    pub fn check(&mut self, px: f64, ts_ms: i64) -> BreakerReport {
+        let longest_ms = self.cfg.windows.iter().map(|w| w.secs as i64 * 1000).max().unwrap_or(0);
+        while self.history.front().is_some_and(|(t, _)| ts_ms - t > longest_ms.max(self.cfg.settle_ms as i64)) {
+            self.history.pop_front();
+        }
+        let windows: Vec<WindowReport> = self
+            .cfg
+            .windows
+            .iter()
+            .map(|w| WindowReport { secs: w.secs, max_move: w.max_move, observed: self.max_move_since(px, ts_ms - w.secs as i64 * 1000) })
+            .collect();
+        self.history.push_back((ts_ms, px));
+
+        let event = match self.tripped_since_ms {
+            None if windows.iter().any(|w| w.observed > w.max_move) => {
+                self.tripped_since_ms = Some(ts_ms);
+                Some(BreakerEvent::Tripped)
+            }
+            Some(since) if self.settled(since, ts_ms) => {
+                self.tripped_since_ms = None;
+                self.history.clear();
+                self.history.push_back((ts_ms, px));
+                Some(BreakerEvent::Reset)
+            }
+            _ => None,
+        };
+        BreakerReport { windows, tripped: self.tripped_since_ms.is_some(), tripped_since_ms: self.tripped_since_ms, event }
+    }
+
+    /// Largest |px / p − 1| over recorded prices from `from_ms` on.
+    fn max_move_since(&self, px: f64, from_ms: i64) -> f64 {
+        self.history.iter().filter(|(t, _)| *t >= from_ms).map(|(_, p)| (px / p - 1.0).abs()).fold(0.0, f64::max)
+    }
+
+    fn settled(&self, since: i64, now: i64) -> bool {
+        let held = now - since;
+        if held < self.cfg.cooldown_ms as i64 || held < self.cfg.settle_ms as i64 {
+            return false;
+        }
+        // The window starts at the level the price held coming into it.
+        let from = since.max(now - self.cfg.settle_ms as i64);
+        let start = self.history.iter().position(|(t, _)| *t >= from).unwrap_or(self.history.len()).saturating_sub(1);
+        let recent = || self.history.iter().skip(start).filter(|(t, _)| *t >= since).map(|(_, p)| *p);
+        let (lo, hi) = (recent().fold(f64::INFINITY, f64::min), recent().fold(0.0, f64::max));
+        let limit = self.cfg.windows.first().map_or(0.0, |w| w.max_move);
+        hi / lo - 1.0 <= limit
+    }
+}
+
+/// What `CircuitBreaker` needs to carry on after a restart.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BreakerState {
+    pub history: Vec<(i64, f64)>, // (ts_ms, price), oldest first
+    pub tripped_since_ms: Option<i64>,
+}
+
+impl CircuitBreaker {
+    pub fn state(&self) -> BreakerState {
+        BreakerState { history: self.history.iter().copied().collect(), tripped_since_ms: self.tripped_since_ms }
+    }
+
+    /// Entries older than the longest window are pruned on the next `check`.
+    pub fn restore(&mut self, state: BreakerState) {
+        self.history = state.history.into();
+        self.tripped_since_ms = state.tripped_since_ms;
+    }
+}
+
 pub struct RiskEngine {
-    /// Maximum allowed absolute percent move over a 60s lookback (e.g., 0.08 = 8%)
-    pub max_delta_pct_60s: f64,
-    /// (last_price, last_ts_ms)
-    last_px: Option<(f64, i64)>,
+    pub breaker: CircuitBreaker,
+    clock: SharedClock,
 }
 
 impl RiskEngine {
-    pub fn new(max_delta_pct_60s: f64) -> Self {
-        Self { max_delta_pct_60s, last_px: None }
+    pub fn new(breaker: BreakerConfig) -> Self {
+        Self { breaker: CircuitBreaker::new(breaker), clock: clock::system() }
+    }
+
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
     }
 
     /// Simplified trading-hours check: Mon–Fri 09:00–14:00 in a caller-supplied local offset.
     /// Replace with a real exchange calendar when ready.
     pub fn trading_hours_open(&self, tz_offset_hours: i32) -> bool {
-        let now = chrono::Utc::now() + chrono::Duration::hours(tz_offset_hours as i64);
+        let now = self.clock.now() + chrono::Duration::hours(tz_offset_hours as i64);
         let wd = now.weekday().number_from_monday(); // 1..=5
         let h = now.hour() as i32;                   // 0..23
         (1..=5).contains(&wd) && (9..=14).contains(&h)
     }
 
-    /// One-step circuit breaker: trips if an absolute return over the last ~60s
-    /// exceeds `max_delta_pct_60s`. You can pass an optional authoritative
-    /// `(price, ts_ms)` as `last_good` (e.g., last published fused price).
-    pub fn eval_circuit_breaker(
-        &mut self,
-        last_good: Option<(f64, i64)>,
-        new_px: f64,
-        now_ms: i64,
-    ) -> bool {
-        let base = last_good
-            .or(self.last_px)
-            .unwrap_or((new_px, now_ms));
-        let dt = (now_ms - base.1).max(1) as f64 / 1000.0; // seconds
-        let dv = ((new_px - base.0) / base.0).abs();
-        self.last_px = Some((new_px, now_ms));
-        dt <= 60.0 && dv > self.max_delta_pct_60s
+    /// Run a candidate mark through the circuit breaker (see `CircuitBreaker`).
+    pub fn eval_circuit_breaker(&mut self, new_px: f64, now_ms: i64) -> BreakerReport {
+        self.breaker.check(new_px, now_ms)
     }
 
     /// Map index errors to short, user-facing risk codes.
@@ -60,21 +168,19 @@ impl RiskEngine {
 
     /// Convenience helper to compute all risk switches at once.
     /// - `tz_offset_hours`: local offset for hours gating
-    /// - `last_good`: optional authoritative last price
-    /// - `maybe_new_px`: optional new tick to test breaker (price, ts_ms)
+    /// - `maybe_new_px`: optional new tick to test breaker (price, ts_ms); without
+    ///   one the switch reports whether the breaker is still tripped
     /// - `roll_active`: set by your roll scheduler
     pub fn compute_switches(
         &mut self,
         tz_offset_hours: i32,
-        last_good: Option<(f64, i64)>,
         maybe_new_px: Option<(f64, i64)>,
         roll_active: bool,
     ) -> RiskSwitches {
         let hours_open = self.trading_hours_open(tz_offset_hours);
-        let circuit_breaker = if let Some((px, ts)) = maybe_new_px {
-            self.eval_circuit_breaker(last_good, px, ts)
-        } else {
-            false
+        let circuit_breaker = match maybe_new_px {
+            Some((px, ts)) => self.eval_circuit_breaker(px, ts).tripped,
+            None => self.breaker.tripped_since_ms().is_some(),
         };
         RiskSwitches {
             circuit_breaker,
@@ -83,3 +189,50 @@ impl RiskEngine {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::sync::Arc;
+
+    #[test]
+    fn hours_gate_uses_the_clock() {
+        let clock = ManualClock::new(1_700_474_400_000); // Mon 2023-11-20 10:00 UTC
+        let risk = RiskEngine::new(BreakerConfig::from(&OracleConfig::default())).with_clock(Arc::new(clock.clone()));
+        assert!(risk.trading_hours_open(0));
+        assert!(!risk.trading_hours_open(-5)); // 05:00 local
+        clock.advance_ms(5 * 86_400_000); // Saturday
+        assert!(!risk.trading_hours_open(0));
+    }
+
+    #[test]
+    fn breaker_trips_per_window_and_resets_once_settled() {
+        let cfg = OracleConfig {
+            circuit_breaker_per_min: 0.02,
+            circuit_breaker_windows: vec![BreakerWindow { secs: 300, max_move: 0.03 }],
+            circuit_breaker_cooldown_ms: 60_000,
+            circuit_breaker_settle_ms: 30_000,
+            ..OracleConfig::default()
+        };
+        let mut cb = CircuitBreaker::new(BreakerConfig::from(&cfg));
+        let t = |min: i64| min * 60_000;
+
+        // +1.5% a minute: each minute is fine, but 5m catches the drift.
+        assert!(!cb.check(100.0, 0).tripped);
+        assert!(!cb.check(101.5, t(1) + 1).tripped);
+        let r = cb.check(103.5, t(2) + 2);
+        assert_eq!((r.tripped, r.event), (true, Some(BreakerEvent::Tripped)));
+        assert!(r.windows[0].observed < 0.02 && r.windows[1].observed > 0.03, "{:?}", r.windows);
+
+        // Holds through the cooldown, and after it while the price keeps moving.
+        assert!(cb.check(103.5, t(2) + 30_000).tripped);
+        assert!(cb.check(106.0, t(3) + 10_000).tripped);
+        let r = cb.check(106.1, t(3) + 45_000);
+        assert_eq!((r.tripped, r.event), (false, Some(BreakerEvent::Reset)));
+
+        // The settled level is the new reference for every window.
+        assert!(!cb.check(106.2, t(4)).tripped);
+        assert_eq!(cb.tripped_since_ms(), None);
+    }
+}
@@ -0,0 +1,263 @@
+// src/rounds.rs
+//! Chainlink `AggregatorV3Interface`-style rounds over published marks.
+//!
+//! Every `IndexTick` that reaches the `RoundBook` opens a new round with the next
+//! round id for its symbol (ids start at 1 and never repeat, including across
+//! restarts). The last `capacity` rounds per symbol are kept in memory, which is
+//! what the read API serves. With a path, each round is also appended as one
+//! JSON line to `<path>.log`; every `capacity` rounds, and on open, the history
+//! is written atomically to `<path>` and the log starts over, so a tick costs
+//! one small append rather than a rewrite of the whole history.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::clock::{self, SharedClock};
+use crate::publishing::{web2_cache::write_atomic, Publisher};
+use crate::types::{scale_by_expo, FundingUpdate, IndexTick};
+
+pub const ROUNDS_VERSION: u32 = 1;
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Same fields as `latestRoundData()` / `getRoundData()`; `answer` is scaled by `10^-expo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundData {
+    pub round_id: u64,
+    pub answer: i64,
+    pub expo: i8,
+    pub started_at: i64,        // unix seconds of the consensus that produced the answer
+    pub updated_at: i64,        // unix seconds when the round was recorded
+    pub answered_in_round: u64, // always == round_id: every round carries a fresh answer
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolRounds {
+    pub latest_round: u64,
+    pub rounds: VecDeque<RoundData>, // oldest first
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoundHistory {
+    pub version: u32,
+    pub symbols: BTreeMap<String, SymbolRounds>,
+}
+
+/// One line of the round log.
+#[derive(Debug, Serialize, Deserialize)]
+struct LoggedRound {
+    symbol: String,
+    #[serde(flatten)]
+    round: RoundData,
+}
+
+struct RoundLog {
+    file: tokio::fs::File,
+    lines: usize, // appended since the last snapshot
+}
+
+pub struct RoundBook {
+    path: Option<PathBuf>,
+    capacity: usize,
+    state: RwLock<RoundHistory>,
+    log: Mutex<Option<RoundLog>>, // serialises appends so they land in round order
+    clock: SharedClock,           // stamps `updated_at`
+}
+
+fn log_path(path: &Path) -> PathBuf {
+    let mut p = path.as_os_str().to_owned();
+    p.push(".log");
+    p.into()
+}
+
+impl RoundHistory {
+    fn push(&mut self, symbol: &str, round: RoundData, capacity: usize) {
+        let s = self.symbols.entry(symbol.to_string()).or_default();
+        s.latest_round = s.latest_round.max(round.round_id);
+        s.rounds.push_back(round);
+        while s.rounds.len() > capacity {
+            s.rounds.pop_front();
+        }
+    }
+
+    /// Apply the rounds in `log` that the snapshot doesn't have yet. Only the last
+    /// line may be torn (a crash mid-append); that round is dropped.
+    fn replay(&mut self, log: &[u8], capacity: usize, path: &Path) -> anyhow::Result<()> {
+        let lines: Vec<&[u8]> = log.split(|b| *b == b'\n').filter(|l| !l.is_empty()).collect();
+        for (i, line) in lines.iter().enumerate() {
+            let logged = match serde_json::from_slice::<LoggedRound>(line) {
+                Ok(l) => l,
+                Err(_) if i + 1 == lines.len() => break,
+                Err(e) => return Err(e).with_context(|| format!("parsing round log {} line {}", path.display(), i + 1)),
+            };
+            let seen = self.symbols.get(&logged.symbol).map_or(0, |s| s.latest_round);
+            if logged.round.round_id > seen {
+                self.push(&logged.symbol, logged.round, capacity);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RoundBook {
+    /// History kept only in memory.
+    pub fn in_memory(capacity: usize) -> Self {
+        Self::new(None, capacity.max(1), RoundHistory::default())
+    }
+
+    fn new(path: Option<PathBuf>, capacity: usize, state: RoundHistory) -> Self {
+        Self { path, capacity, state: RwLock::new(state), log: Mutex::new(None), clock: clock::system() }
+    }
+
+    /// Loads the history at `path` and its log, if they exist, and starts a new
+    /// snapshot. A file that doesn't parse is an error rather than a fresh start,
+    /// since that would reuse round ids.
+    pub async fn open<P: Into<PathBuf>>(path: P, capacity: usize) -> anyhow::Result<Self> {
+        let path = path.into();
+        let capacity = capacity.max(1);
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let mut state = match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice::<RoundHistory>(&bytes)
+                .with_context(|| format!("parsing round history {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => RoundHistory::default(),
+            Err(e) => return Err(e.into()),
+        };
+        let log = log_path(&path);
+        match tokio::fs::read(&log).await {
+            Ok(bytes) => state.replay(&bytes, capacity, &log)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        state.version = ROUNDS_VERSION;
+        let book = Self::new(Some(path), capacity, state);
+        *book.log.lock().await = Some(book.snapshot().await?);
+        Ok(book)
+    }
+
+    /// Write the whole history to `path` and start an empty log.
+    async fn snapshot(&self) -> anyhow::Result<RoundLog> {
+        let path = self.path.as_deref().context("round book has no path")?;
+        let bytes = serde_json::to_vec(&*self.state.read().unwrap())?;
+        write_atomic(path, &bytes).await?;
+        let file = tokio::fs::File::create(log_path(path)).await?;
+        Ok(RoundLog { file, lines: 0 })
+    }
+
+    /// Stamp `updated_at` from `clock` instead of the wall clock (replays, tests).
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn path(&self) -> Option<&Path> { self.path.as_deref() }
+
+    pub fn symbols(&self) -> Vec<String> {
+        self.state.read().unwrap().symbols.keys().cloned().collect()
+    }
+
+    pub fn latest_round_data(&self, symbol: &str) -> Option<RoundData> {
+        self.state.read().unwrap().symbols.get(symbol)?.rounds.back().copied()
+    }
+
+    /// `None` for ids that were never issued or have been pruned from the history.
+    pub fn get_round_data(&self, symbol: &str, round_id: u64) -> Option<RoundData> {
+        let state = self.state.read().unwrap();
+        let rounds = &state.symbols.get(symbol)?.rounds;
+        let first = rounds.front()?.round_id;
+        let r = rounds.get(round_id.checked_sub(first)? as usize)?;
+        (r.round_id == round_id).then_some(*r)
+    }
+
+    /// Open a new round for `tick` and append it to the log.
+    pub async fn record(&self, tick: &IndexTick) -> anyhow::Result<RoundData> {
+        let scaled = scale_by_expo(tick.price, tick.expo).map_err(|e| anyhow::anyhow!(e))?;
+        let answer = i64::try_from(scaled).context("scaled answer overflows i64")?;
+        let mut log = self.log.lock().await;
+        let round = {
+            let mut state = self.state.write().unwrap();
+            state.version = ROUNDS_VERSION;
+            let round_id = state.symbols.get(&tick.symbol).map_or(0, |s| s.latest_round) + 1;
+            let round = RoundData {
+                round_id,
+                answer,
+                expo: tick.expo,
+                started_at: tick.ts_ms.div_euclid(1000),
+                updated_at: self.clock.now_ms().div_euclid(1000),
+                answered_in_round: round_id,
+            };
+            state.push(&tick.symbol, round, self.capacity);
+            round
+        };
+        if let Some(l) = log.as_mut() {
+            let mut line = serde_json::to_vec(&LoggedRound { symbol: tick.symbol.clone(), round })?;
+            line.push(b'\n');
+            l.file.write_all(&line).await?;
+            l.file.flush().await?;
+            l.lines += 1;
+            if l.lines >= self.capacity {
+                *l = self.snapshot().await?;
+            }
+        }
+        Ok(round)
+    }
+}
+
+#[async_trait::async_trait]
+impl Publisher for RoundBook {
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        self.record(&tick).await.map(|_| ())
+    }
+    async fn publish_funding(&self, _fu: FundingUpdate) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn assigns_ids_prunes_and_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rounds.json");
+        let book = RoundBook::open(&path, 2).await.unwrap().with_clock(Arc::new(ManualClock::new(1_700_000_005_500)));
+        for (i, px) in [70.0, 71.0, 72.5].into_iter().enumerate() {
+            book.record(&IndexTick::fixture("WTI_PERP", px, 1_700_000_000_000 + i as i64 * 1000)).await.unwrap();
+        }
+        let latest = book.latest_round_data("WTI_PERP").unwrap();
+        assert_eq!((latest.round_id, latest.answer, latest.started_at), (3, 7_250_000_000, 1_700_000_002));
+        assert_eq!((latest.updated_at, latest.answered_in_round), (1_700_000_005, 3));
+        assert!(book.get_round_data("WTI_PERP", 1).is_none()); // pruned
+        assert_eq!(book.get_round_data("WTI_PERP", 2).unwrap().answer, 7_100_000_000);
+        assert!(book.get_round_data("WTI_PERP", 4).is_none());
+
+        // Rounds 1-2 are in the snapshot, round 3 only in the log.
+        let snapshot: RoundHistory = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(snapshot.symbols["WTI_PERP"].latest_round, 2);
+        drop(book);
+        let reopened = RoundBook::open(&path, 2).await.unwrap();
+        assert_eq!(reopened.latest_round_data("WTI_PERP"), Some(latest));
+        assert_eq!(reopened.record(&IndexTick::fixture("WTI_PERP", 73.0, 1_700_000_003_000)).await.unwrap().round_id, 4);
+
+        // A torn last line (crash mid-append) loses only that round.
+        drop(reopened);
+        let mut log = std::fs::read(log_path(&path)).unwrap();
+        log.extend_from_slice(b"{\"symbol\":\"WTI_PE");
+        std::fs::write(log_path(&path), log).unwrap();
+        let reopened = RoundBook::open(&path, 2).await.unwrap();
+        assert_eq!(reopened.latest_round_data("WTI_PERP").unwrap().round_id, 4);
+        drop(reopened);
+
+        std::fs::write(&path, b"{not json").unwrap();
+        assert!(RoundBook::open(&path, 2).await.is_err());
+    }
+}
@@ -0,0 +1,194 @@
+// src/server/grpc.rs
+//! tonic service over the `MarketStore` (schema: `proto/oracle.proto`):
+//!   Subscribe(symbols) -> stream of marks / stats / funding as they are published
+//!   Latest(symbol)     -> current snapshot
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::pin::Pin;
+
+use futures::Stream;
+use tokio::sync::broadcast::error::RecvError;
+use tonic::{Request, Response, Status};
+
+use super::{market_symbol, MarketEvent, MarketStore};
+use crate::types::{self, scale_by_expo};
+
+pub mod pb {
+    tonic::include_proto!("autonom.oracle.v1");
+}
+
+use pb::oracle_feed_server::{OracleFeed, OracleFeedServer};
+
+impl From<&types::IndexTick> for pb::IndexTick {
+    fn from(t: &types::IndexTick) -> Self {
+        Self {
+            symbol: t.symbol.clone(),
+            price: t.price,
+            expo: t.expo as i32,
+            price_scaled: scale_by_expo(t.price, t.expo).unwrap_or(0),
+            ts_ms: t.ts_ms,
+            source: t.source.to_string(),
+            window_sec: t.window_sec,
+        }
+    }
+}
+
+impl From<&types::ConsensusStats> for pb::ConsensusStats {
+    fn from(s: &types::ConsensusStats) -> Self {
+        Self {
+            n_fresh: s.n_fresh as u32,
+            n_used: s.n_used as u32,
+            n_dropped: s.n_dropped as u32,
+            spread_bps: s.spread_bps,
+            confidence: s.confidence,
+            conf: s.conf,
+        }
+    }
+}
+
+impl From<&types::FundingUpdate> for pb::FundingUpdate {
+    fn from(f: &types::FundingUpdate) -> Self {
+        Self {
+            symbol: f.symbol.clone(),
+            rate: f.rate,
+            interval_sec: f.interval_sec,
+            ts_ms: f.ts_ms,
+            predicted: f.kind == types::FundingKind::Predicted,
+            settle_ms: f.settle_ms,
+        }
+    }
+}
+
+impl From<&MarketEvent> for pb::MarketUpdate {
+    fn from(ev: &MarketEvent) -> Self {
+        use pb::market_update::Payload;
+        let payload = match ev {
+            MarketEvent::Mark { tick, .. } => Payload::Mark(tick.into()),
+            MarketEvent::Stats { stats, .. } => Payload::Stats(stats.into()),
+            MarketEvent::Funding { update, .. } => Payload::Funding(update.into()),
+        };
+        Self { symbol: ev.symbol().to_string(), payload: Some(payload) }
+    }
+}
+
+pub struct FeedService {
+    store: MarketStore,
+}
+
+impl FeedService {
+    pub fn new(store: MarketStore) -> Self { Self { store } }
+}
+
+type UpdateStream = Pin<Box<dyn Stream<Item = Result<pb::MarketUpdate, Status>> + Send + 'static>>;
+
+#[tonic::async_trait]
+impl OracleFeed for FeedService {
+    type SubscribeStream = UpdateStream;
+
+    async fn subscribe(
+        &self,
+        req: Request<pb::SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        // Accept either market symbols or their "-PERP" funding names.
+        let wanted: HashSet<String> = req
+            .into_inner()
+            .symbols
+            .iter()
+            .map(|s| market_symbol(s).to_string())
+            .collect();
+        let rx = self.store.subscribe();
+
+        let stream = futures::stream::unfold((rx, wanted), |(mut rx, wanted)| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(ev) if wanted.is_empty() || wanted.contains(ev.symbol()) => {
+                        return Some((Ok(pb::MarketUpdate::from(&ev)), (rx, wanted)));
+                    }
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!("grpc subscriber lagged, skipped {n} updates");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn latest(
+        &self,
+        req: Request<pb::LatestRequest>,
+    ) -> Result<Response<pb::LatestResponse>, Status> {
+        let symbol = req.into_inner().symbol;
+        let snap = self
+            .store
+            .snapshot(market_symbol(&symbol))
+            .ok_or_else(|| Status::not_found(format!("no data for {symbol}")))?;
+        Ok(Response::new(pb::LatestResponse {
+            symbol,
+            mark: snap.mark.as_ref().map(Into::into),
+            stats: snap.stats.as_ref().map(Into::into),
+            funding: snap.funding.as_ref().map(Into::into),
+            predicted_funding: snap.predicted_funding.as_ref().map(Into::into),
+            updated_ms: snap.updated_ms,
+        }))
+    }
+}
+
+/// Bind and serve until the task is dropped/aborted.
+pub async fn serve(addr: SocketAddr, store: MarketStore) -> anyhow::Result<()> {
+    tracing::info!("grpc feed listening on {addr}");
+    tonic::transport::Server::builder()
+        .add_service(OracleFeedServer::new(FeedService::new(store)))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::publishing::Publisher;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn subscribe_filters_symbols_and_latest_reads_snapshot() {
+        let store = MarketStore::new();
+        let svc = FeedService::new(store.clone());
+        let mut stream = svc
+            .subscribe(Request::new(pb::SubscribeRequest { symbols: vec!["GOLD_PERP".into()] }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let tick = |symbol: &str, price: f64| types::IndexTick {
+            symbol: symbol.into(),
+            price,
+            expo: -8,
+            ts_ms: 1_700_000_000_000,
+            source: "cfd-consensus",
+            window_sec: 0,
+        };
+        store.publish_index(tick("SILVER_PERP", 24.1)).await.unwrap();
+        store.publish_index(tick("GOLD_PERP", 2_400.5)).await.unwrap();
+
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.symbol, "GOLD_PERP");
+        match first.payload {
+            Some(pb::market_update::Payload::Mark(m)) => assert_eq!(m.price_scaled, 240_050_000_000),
+            other => panic!("unexpected payload {other:?}"),
+        }
+
+        let latest = svc
+            .latest(Request::new(pb::LatestRequest { symbol: "SILVER_PERP".into() }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(latest.mark.unwrap().price, 24.1);
+
+        let missing = svc.latest(Request::new(pb::LatestRequest { symbol: "CORN_PERP".into() })).await;
+        assert_eq!(missing.unwrap_err().code(), tonic::Code::NotFound);
+    }
+}
@@ -0,0 +1,217 @@
+// src/server/http.rs
+//! JSON read API over the `MarketStore`:
+//!   GET /v1/symbols
+//!   GET /v1/marks/{symbol}
+//!   GET /v1/funding/{symbol}           (last settlement and the running prediction)
+//!   GET /v1/rounds/{symbol}/latest       (when a `RoundBook` is attached)
+//!   GET /v1/rounds/{symbol}/{round_id}
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::json;
+use tower_http::compression::CompressionLayer;
+use tower_http::cors::CorsLayer;
+use tower_http::trace::TraceLayer;
+
+use super::MarketStore;
+use crate::metrics;
+use crate::rounds::RoundBook;
+
+/// Everything the handlers read from.
+#[derive(Clone)]
+pub struct AppState {
+    pub store: MarketStore,
+    pub rounds: Option<Arc<RoundBook>>,
+}
+
+impl AppState {
+    pub fn new(store: MarketStore) -> Self { Self { store, rounds: None } }
+
+    pub fn with_rounds(mut self, rounds: Arc<RoundBook>) -> Self {
+        self.rounds = Some(rounds);
+        self
+    }
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/v1/symbols", get(symbols))
+        .route("/v1/marks/{symbol}", get(mark))
+        .route("/v1/funding/{symbol}", get(funding))
+        .route("/v1/rounds/{symbol}/latest", get(latest_round))
+        .route("/v1/rounds/{symbol}/{round_id}", get(round))
+        .layer(CompressionLayer::new())
+        .layer(CorsLayer::permissive())
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
+
+/// Bind and serve until the task is dropped/aborted.
+pub async fn serve(addr: SocketAddr, state: AppState) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("http api listening on {}", listener.local_addr()?);
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+fn respond(endpoint: &str, status: StatusCode, body: serde_json::Value) -> Response {
+    metrics::RESPONSES_TOTAL
+        .with_label_values(&[endpoint, status.as_str()])
+        .inc();
+    (status, Json(body)).into_response()
+}
+
+fn not_found(endpoint: &str, what: &str, symbol: &str) -> Response {
+    respond(endpoint, StatusCode::NOT_FOUND, json!({ "error": format!("no {what} for {symbol}") }))
+}
+
+async fn symbols(State(state): State<AppState>) -> Response {
+    metrics::REQUESTS_TOTAL.with_label_values(&["symbols"]).inc();
+    respond("symbols", StatusCode::OK, json!({ "symbols": state.store.symbols() }))
+}
+
+async fn mark(State(state): State<AppState>, Path(symbol): Path<String>) -> Response {
+    metrics::REQUESTS_TOTAL.with_label_values(&["marks"]).inc();
+    match state.store.snapshot(&symbol) {
+        Some(snap) if snap.mark.is_some() => respond(
+            "marks",
+            StatusCode::OK,
+            json!({
+                "symbol": symbol,
+                "mark": snap.mark,
+                "stats": snap.stats,
+                "price_feed": snap.price_feed,
+                "health": snap.health,
+                "signed": snap.signed,
+                "updated_ms": snap.updated_ms,
+            }),
+        ),
+        _ => not_found("marks", "mark", &symbol),
+    }
+}
+
+async fn funding(State(state): State<AppState>, Path(symbol): Path<String>) -> Response {
+    metrics::REQUESTS_TOTAL.with_label_values(&["funding"]).inc();
+    match state.store.snapshot(super::market_symbol(&symbol)) {
+        Some(snap) if snap.funding.is_some() || snap.predicted_funding.is_some() => respond(
+            "funding",
+            StatusCode::OK,
+            json!({
+                "symbol": symbol,
+                "funding": snap.funding,
+                "predicted": snap.predicted_funding,
+                "updated_ms": snap.updated_ms,
+            }),
+        ),
+        _ => not_found("funding", "funding", &symbol),
+    }
+}
+
+async fn latest_round(State(state): State<AppState>, Path(symbol): Path<String>) -> Response {
+    metrics::REQUESTS_TOTAL.with_label_values(&["rounds"]).inc();
+    match state.rounds.as_ref().and_then(|b| b.latest_round_data(&symbol)) {
+        Some(r) => respond("rounds", StatusCode::OK, json!({ "symbol": symbol, "round": r })),
+        None => not_found("rounds", "rounds", &symbol),
+    }
+}
+
+async fn round(State(state): State<AppState>, Path((symbol, round_id)): Path<(String, u64)>) -> Response {
+    metrics::REQUESTS_TOTAL.with_label_values(&["rounds"]).inc();
+    match state.rounds.as_ref().and_then(|b| b.get_round_data(&symbol, round_id)) {
+        Some(r) => respond("rounds", StatusCode::OK, json!({ "symbol": symbol, "round": r })),
+        None => not_found("rounds", &format!("round {round_id}"), &symbol),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::publishing::Publisher;
+    use crate::types::{FundingKind, FundingUpdate, IndexTick};
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn get_json(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let resp = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn serves_latest_mark_and_funding() {
+        let store = MarketStore::new();
+        store.publish_index(IndexTick {
+            symbol: "LEAN_HOGS_PERP".into(),
+            price: 0.9123,
+            expo: -8,
+            ts_ms: 1_700_000_000_000,
+            source: "cfd-consensus",
+            window_sec: 0,
+        }).await.unwrap();
+        store.publish_funding(FundingUpdate {
+            symbol: "LEAN_HOGS_PERP-PERP".into(),
+            rate: 0.0001,
+            interval_sec: 8 * 3600,
+            ts_ms: 1_700_000_000_000,
+            kind: FundingKind::Settled,
+            settle_ms: 1_699_920_000_000,
+        }).await.unwrap();
+
+        let (st, body) = get_json(router(AppState::new(store.clone())), "/v1/symbols").await;
+        assert_eq!(st, StatusCode::OK);
+        assert_eq!(body["symbols"][0], "LEAN_HOGS_PERP");
+
+        let (st, body) = get_json(router(AppState::new(store.clone())), "/v1/marks/LEAN_HOGS_PERP").await;
+        assert_eq!(st, StatusCode::OK);
+        assert_eq!(body["mark"]["price"], 0.9123);
+
+        let (st, body) = get_json(router(AppState::new(store.clone())), "/v1/funding/LEAN_HOGS_PERP").await;
+        assert_eq!(st, StatusCode::OK);
+        assert_eq!(body["funding"]["rate"], 0.0001);
+        assert!(body["predicted"].is_null());
+
+        let (st, _) = get_json(router(AppState::new(store)), "/v1/marks/CORN_PERP").await;
+        assert_eq!(st, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn serves_rounds() {
+        let book = Arc::new(RoundBook::in_memory(8));
+        for price in [2.0, 2.5] {
+            book.publish_index(IndexTick {
+                symbol: "NATGAS_PERP".into(),
+                price,
+                expo: -8,
+                ts_ms: 1_700_000_000_000,
+                source: "cfd-consensus",
+                window_sec: 0,
+            }).await.unwrap();
+        }
+        let app = || router(AppState::new(MarketStore::new()).with_rounds(book.clone()));
+
+        let (st, body) = get_json(app(), "/v1/rounds/NATGAS_PERP/latest").await;
+        assert_eq!(st, StatusCode::OK);
+        assert_eq!(body["round"]["round_id"], 2);
+        assert_eq!(body["round"]["answer"], 250_000_000);
+
+        let (st, body) = get_json(app(), "/v1/rounds/NATGAS_PERP/1").await;
+        assert_eq!(st, StatusCode::OK);
+        assert_eq!(body["round"]["answer"], 200_000_000);
+
+        let (st, _) = get_json(app(), "/v1/rounds/NATGAS_PERP/3").await;
+        assert_eq!(st, StatusCode::NOT_FOUND);
+        let (st, _) = get_json(router(AppState::new(MarketStore::new())), "/v1/rounds/NATGAS_PERP/latest").await;
+        assert_eq!(st, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,154 @@
+// src/server/mod.rs
+//! Read-side of the oracle: an in-memory store of the latest published state per
+//! symbol, plus the servers that expose it.
+
+pub mod grpc;
+pub mod http;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::health::OracleHealth;
+use crate::publishing::Publisher;
+use crate::signing::SignedIndexTick;
+use crate::types::{ConsensusStats, FundingKind, FundingUpdate, IndexTick, PriceFeed};
+
+/// Latest known state for one market.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MarketSnapshot {
+    pub mark: Option<IndexTick>,
+    pub signed: Option<SignedIndexTick>, // attestation for `mark`, when signing is on
+    pub stats: Option<ConsensusStats>,
+    pub price_feed: Option<PriceFeed>,
+    pub health: Option<OracleHealth>,
+    pub funding: Option<FundingUpdate>,           // last settlement
+    pub predicted_funding: Option<FundingUpdate>, // for the next settlement
+    pub updated_ms: i64, // wall-clock ms of the last write to this snapshot
+}
+
+/// One published update, fanned out to live subscribers (gRPC streams).
+/// `symbol` is always the market symbol.
+#[derive(Debug, Clone)]
+pub enum MarketEvent {
+    Mark { symbol: String, tick: IndexTick },
+    Stats { symbol: String, stats: ConsensusStats },
+    Funding { symbol: String, update: FundingUpdate },
+}
+
+impl MarketEvent {
+    pub fn symbol(&self) -> &str {
+        match self {
+            MarketEvent::Mark { symbol, .. }
+            | MarketEvent::Stats { symbol, .. }
+            | MarketEvent::Funding { symbol, .. } => symbol,
+        }
+    }
+}
+
+/// Slow subscribers that fall this far behind skip ahead (and miss updates).
+const EVENT_BUFFER: usize = 1024;
+
+/// Shared, cheaply clonable store. Register it as a `Publisher` on the oracle and
+/// hand clones to the HTTP/gRPC servers.
+#[derive(Clone)]
+pub struct MarketStore {
+    inner: Arc<RwLock<HashMap<String, MarketSnapshot>>>,
+    events: broadcast::Sender<MarketEvent>,
+}
+
+impl Default for MarketStore {
+    fn default() -> Self {
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        Self { inner: Arc::default(), events }
+    }
+}
+
+impl MarketStore {
+    pub fn new() -> Self { Self::default() }
+
+    /// Receive every update published after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<MarketEvent> {
+        self.events.subscribe()
+    }
+
+    pub fn snapshot(&self, symbol: &str) -> Option<MarketSnapshot> {
+        self.inner.read().unwrap().get(symbol).cloned()
+    }
+
+    pub fn snapshots(&self) -> BTreeMap<String, MarketSnapshot> {
+        self.inner.read().unwrap().iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+
+    pub fn symbols(&self) -> Vec<String> {
+        let mut v: Vec<String> = self.inner.read().unwrap().keys().cloned().collect();
+        v.sort();
+        v
+    }
+
+    fn update<F: FnOnce(&mut MarketSnapshot)>(&self, symbol: &str, f: F) {
+        let mut map = self.inner.write().unwrap();
+        let snap = map.entry(symbol.to_string()).or_default();
+        f(snap);
+        snap.updated_ms = chrono::Utc::now().timestamp_millis();
+    }
+
+    fn emit(&self, ev: MarketEvent) {
+        // No receivers is fine; nobody is streaming right now.
+        let _ = self.events.send(ev);
+    }
+}
+
+/// `FundingEngine` tags updates as `<symbol>-PERP`; key them by the market symbol.
+pub fn market_symbol(funding_symbol: &str) -> &str {
+    funding_symbol.strip_suffix("-PERP").unwrap_or(funding_symbol)
+}
+
+#[async_trait::async_trait]
+impl Publisher for MarketStore {
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        let symbol = tick.symbol.clone();
+        self.update(&symbol, |s| {
+            s.mark = Some(tick.clone());
+            s.signed = None;
+        });
+        self.emit(MarketEvent::Mark { symbol, tick });
+        Ok(())
+    }
+    async fn publish_funding(&self, fu: FundingUpdate) -> anyhow::Result<()> {
+        let symbol = market_symbol(&fu.symbol).to_string();
+        self.update(&symbol, |s| match fu.kind {
+            FundingKind::Settled => s.funding = Some(fu.clone()),
+            FundingKind::Predicted => s.predicted_funding = Some(fu.clone()),
+        });
+        self.emit(MarketEvent::Funding { symbol, update: fu });
+        Ok(())
+    }
+    async fn publish_consensus(&self, symbol: &str, stats: ConsensusStats) -> anyhow::Result<()> {
+        self.update(symbol, |s| s.stats = Some(stats));
+        self.emit(MarketEvent::Stats { symbol: symbol.to_string(), stats });
+        Ok(())
+    }
+    async fn publish_signed(&self, signed: SignedIndexTick) -> anyhow::Result<()> {
+        let symbol = signed.tick.symbol.clone();
+        let tick = signed.tick.clone();
+        self.update(&symbol, |s| {
+            s.mark = Some(tick.clone());
+            s.signed = Some(signed);
+        });
+        self.emit(MarketEvent::Mark { symbol, tick });
+        Ok(())
+    }
+    async fn publish_price_feed(&self, feed: PriceFeed) -> anyhow::Result<()> {
+        let symbol = feed.symbol.clone();
+        self.update(&symbol, |s| s.price_feed = Some(feed));
+        Ok(())
+    }
+    async fn publish_health(&self, health: OracleHealth) -> anyhow::Result<()> {
+        let symbol = health.symbol.clone();
+        self.update(&symbol, |s| s.health = Some(health));
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+// src/signing.rs
+//! secp256k1 attestations over published marks.
+//!
+//! Canonical tick encoding (all integers big-endian), hashed with SHA-256:
+//!   u8  version (= 1)
+//!   u16 symbol length, symbol bytes (UTF-8)
+//!   u64 price scaled by `expo` (see `scale_by_expo`)
+//!   i8  expo
+//!   i64 ts_ms
+//!   u8  source length, source bytes (UTF-8)
+//!
+//! The signature is the 64-byte `r || s` (low-S) plus a recovery id, so verifiers
+//! can recover the signer from the digest alone (ecrecover style).
+
+use anyhow::{anyhow, Context, Result};
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::publishing::{policy::Suppressed, Publisher};
+use crate::health::OracleHealth;
+use crate::metrics;
+use crate::types::{scale_by_expo, CfdQuote, ConsensusStats, FundingUpdate, IndexTick, PriceFeed};
+
+pub const ENCODING_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SignedIndexTick {
+    pub tick: IndexTick,
+    pub price_scaled: u64,
+    pub digest: String,      // hex sha256 of the canonical encoding
+    pub signature: String,   // hex r||s (64 bytes)
+    pub recovery_id: u8,     // 0..=3; add 27 for EVM `v`
+    pub signer: String,      // hex SEC1 compressed public key
+}
+
+/// Canonical byte encoding of a tick. Fails for prices `scale_by_expo` rejects.
+pub fn canonical_bytes(tick: &IndexTick) -> Result<Vec<u8>> {
+    let price_scaled = scale_by_expo(tick.price, tick.expo).map_err(|e| anyhow!(e))?;
+    let symbol = tick.symbol.as_bytes();
+    let source = tick.source.as_bytes();
+    let symbol_len = u16::try_from(symbol.len()).map_err(|_| anyhow!("symbol too long"))?;
+    let source_len = u8::try_from(source.len()).map_err(|_| anyhow!("source too long"))?;
+
+    let mut out = Vec::with_capacity(1 + 2 + symbol.len() + 8 + 1 + 8 + 1 + source.len());
+    out.push(ENCODING_VERSION);
+    out.extend_from_slice(&symbol_len.to_be_bytes());
+    out.extend_from_slice(symbol);
+    out.extend_from_slice(&price_scaled.to_be_bytes());
+    out.extend_from_slice(&tick.expo.to_be_bytes());
+    out.extend_from_slice(&tick.ts_ms.to_be_bytes());
+    out.push(source_len);
+    out.extend_from_slice(source);
+    Ok(out)
+}
+
+pub fn tick_digest(tick: &IndexTick) -> Result<[u8; 32]> {
+    Ok(Sha256::digest(canonical_bytes(tick)?).into())
+}
+
+pub struct TickSigner {
+    key: SigningKey,
+}
+
+impl TickSigner {
+    pub fn new(key: SigningKey) -> Self { Self { key } }
+
+    /// 32-byte secret scalar as hex (optional `0x` prefix).
+    pub fn from_hex(secret_hex: &str) -> Result<Self> {
+        let raw = hex::decode(secret_hex.trim().trim_start_matches("0x"))
+            .context("decoding signing key hex")?;
+        let key = SigningKey::from_slice(&raw).map_err(|_| anyhow!("invalid secp256k1 secret key"))?;
+        Ok(Self { key })
+    }
+
+    /// Reads the key from `ORACLE_SIGNING_KEY`.
+    pub fn from_env() -> Result<Self> {
+        let v = std::env::var("ORACLE_SIGNING_KEY")
+            .map_err(|_| anyhow!("Set ORACLE_SIGNING_KEY"))?;
+        Self::from_hex(&v)
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey { self.key.verifying_key() }
+
+    pub fn signer_hex(&self) -> String {
+        hex::encode(self.verifying_key().to_encoded_point(true).as_bytes())
+    }
+
+    pub fn sign(&self, tick: &IndexTick) -> Result<SignedIndexTick> {
+        let price_scaled = scale_by_expo(tick.price, tick.expo).map_err(|e| anyhow!(e))?;
+        let digest = tick_digest(tick)?;
+        let (sig, recid) = self
+            .key
+            .sign_prehash_recoverable(&digest)
+            .map_err(|e| anyhow!("signing failed: {e}"))?;
+        Ok(SignedIndexTick {
+            tick: tick.clone(),
+            price_scaled,
+            digest: hex::encode(digest),
+            signature: hex::encode(sig.to_bytes()),
+            recovery_id: recid.to_byte(),
+            signer: self.signer_hex(),
+        })
+    }
+}
+
+/// Recompute the digest from the tick, recover the signer and check it matches
+/// `signed.signer`. Returns the recovered key.
+pub fn verify(signed: &SignedIndexTick) -> Result<VerifyingKey> {
+    let digest = tick_digest(&signed.tick)?;
+    if hex::encode(digest) != signed.digest {
+        return Err(anyhow!("digest does not match tick"));
+    }
+    let sig = Signature::from_slice(&hex::decode(&signed.signature)?)
+        .map_err(|_| anyhow!("malformed signature"))?;
+    let recid = RecoveryId::from_byte(signed.recovery_id).ok_or_else(|| anyhow!("bad recovery id"))?;
+    let key = VerifyingKey::recover_from_prehash(&digest, &sig, recid)
+        .map_err(|_| anyhow!("signature recovery failed"))?;
+    if hex::encode(key.to_encoded_point(true).as_bytes()) != signed.signer {
+        return Err(anyhow!("recovered signer does not match"));
+    }
+    Ok(key)
+}
+
+/// Signs every mark and hands the attestation to the inner sink via `publish_signed`.
+/// A mark that can't be signed is logged, counted in `signing_errors_total` and
+/// still published unsigned via `publish_index`.
+pub struct SigningPublisher<P> {
+    pub signer: TickSigner,
+    pub inner: P,
+}
+
+impl<P: Publisher> SigningPublisher<P> {
+    pub fn new(signer: TickSigner, inner: P) -> Self { Self { signer, inner } }
+}
+
+#[async_trait::async_trait]
+impl<P: Publisher> Publisher for SigningPublisher<P> {
+    async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+        match self.signer.sign(&tick) {
+            Ok(signed) => self.inner.publish_signed(signed).await,
+            Err(e) => {
+                tracing::warn!("signing {} @{} failed, publishing unsigned: {e:#}", tick.symbol, tick.ts_ms);
+                metrics::SIGNING_ERRORS_TOTAL.with_label_values(&[&tick.symbol]).inc();
+                self.inner.publish_index(tick).await
+            }
+        }
+    }
+    async fn publish_funding(&self, fu: FundingUpdate) -> anyhow::Result<()> {
+        self.inner.publish_funding(fu).await
+    }
+    async fn publish_consensus(&self, symbol: &str, stats: ConsensusStats) -> anyhow::Result<()> {
+        self.inner.publish_consensus(symbol, stats).await
+    }
+    async fn publish_signed(&self, signed: SignedIndexTick) -> anyhow::Result<()> {
+        self.inner.publish_signed(signed).await
+    }
+    async fn publish_quotes(&self, symbol: &str, quotes: &[CfdQuote]) -> anyhow::Result<()> {
+        self.inner.publish_quotes(symbol, quotes).await
+    }
+    async fn publish_price_feed(&self, feed: PriceFeed) -> anyhow::Result<()> {
+        self.inner.publish_price_feed(feed).await
+    }
+    async fn publish_health(&self, health: OracleHealth) -> anyhow::Result<()> {
+        self.inner.publish_health(health).await
+    }
+    fn take_suppressed(&self, symbol: &str) -> Vec<Suppressed> {
+        self.inner.take_suppressed(symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick() -> IndexTick {
+        IndexTick::fixture("LEAN_HOGS_PERP", 0.91234567, 1_700_000_000_123)
+    }
+
+    #[test]
+    fn canonical_encoding_layout() {
+        let b = canonical_bytes(&tick()).unwrap();
+        assert_eq!(b[0], ENCODING_VERSION);
+        assert_eq!(u16::from_be_bytes([b[1], b[2]]), 14);
+        assert_eq!(&b[3..17], b"LEAN_HOGS_PERP");
+        assert_eq!(u64::from_be_bytes(b[17..25].try_into().unwrap()), 91_234_567);
+        assert_eq!(b[25] as i8, -8);
+        assert_eq!(i64::from_be_bytes(b[26..34].try_into().unwrap()), 1_700_000_000_123);
+        assert_eq!(b[34] as usize, "cfd-consensus".len());
+        assert_eq!(&b[35..], b"cfd-consensus");
+    }
+
+    #[test]
+    fn sign_then_recover() {
+        let signer = TickSigner::from_hex(&"11".repeat(32)).unwrap();
+        let signed = signer.sign(&tick()).unwrap();
+        assert_eq!(signed.price_scaled, 91_234_567);
+        let key = verify(&signed).unwrap();
+        assert_eq!(&key, signer.verifying_key());
+
+        let mut tampered = signed.clone();
+        tampered.tick.price = 0.92;
+        assert!(verify(&tampered).is_err());
+    }
+
+    #[derive(Default)]
+    struct Sink {
+        unsigned: std::sync::Mutex<Vec<IndexTick>>,
+        signed: std::sync::Mutex<Vec<SignedIndexTick>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Publisher for Sink {
+        async fn publish_index(&self, tick: IndexTick) -> anyhow::Result<()> {
+            self.unsigned.lock().unwrap().push(tick);
+            Ok(())
+        }
+        async fn publish_funding(&self, _fu: FundingUpdate) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn publish_signed(&self, signed: SignedIndexTick) -> anyhow::Result<()> {
+            self.signed.lock().unwrap().push(signed);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn publishes_unsigned_when_signing_fails() {
+        let p = SigningPublisher::new(TickSigner::from_hex(&"11".repeat(32)).unwrap(), Sink::default());
+        p.publish_index(tick()).await.unwrap();
+        p.publish_index(IndexTick { expo: -6, ..tick() }).await.unwrap(); // can't be scaled, so can't be signed
+        assert_eq!(p.inner.signed.lock().unwrap().len(), 1);
+        assert_eq!(p.inner.unsigned.lock().unwrap()[0].expo, -6);
+    }
+}
@@ -0,0 +1,179 @@
+// src/state.rs
+//! Oracle runtime state that should survive a restart.
+//!
+//! Without it a restarted `Oracle` has no last good mark (so its first ticks are
+//! unclamped), an empty breaker history, a funding EMA that snaps to the first
+//! mark it sees, an empty premium window and a funding interval that starts
+//! over. `Oracle::snapshot` captures that state and `Oracle::restore` puts it
+//! back; `StateStore` keeps one snapshot per market in a JSON file, written
+//! atomically on every save.
+//!
+//! On restore, everything but the provider scores is dropped if it was saved
+//! more than `max_age_ms` ago, as is a last good mark older than that. Provider
+//! scores are kept for `reputation_max_age_ms`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::funding::AccumulatorState;
+use crate::price_feed::PriceFeedState;
+use crate::publishing::web2_cache::write_atomic;
+use crate::reputation::ProviderScore;
+use crate::risk::BreakerState;
+use crate::types::IndexTick;
+
+pub const STATE_VERSION: u32 = 1;
+
+/// `IndexTick` with an owned `source`, so it can be read back from disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedMark {
+    pub price: f64,
+    pub expo: i8,
+    pub ts_ms: i64,
+    pub source: String,
+    pub window_sec: u32,
+}
+
+impl From<&IndexTick> for SavedMark {
+    fn from(t: &IndexTick) -> Self {
+        Self { price: t.price, expo: t.expo, ts_ms: t.ts_ms, source: t.source.to_string(), window_sec: t.window_sec }
+    }
+}
+
+impl SavedMark {
+    pub fn to_tick(&self, symbol: &str) -> IndexTick {
+        let source = match self.source.as_str() {
+            "cfd-consensus" => "cfd-consensus",
+            "hybrid" => "hybrid",
+            "cmf" => "cmf",
+            "cfd" => "cfd",
+            _ => "restored",
+        };
+        IndexTick { symbol: symbol.to_string(), price: self.price, expo: self.expo, ts_ms: self.ts_ms, source, window_sec: self.window_sec }
+    }
+}
+
+/// One market's saved state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OracleState {
+    pub saved_ms: i64,
+    pub last_good_mark: Option<SavedMark>,
+    pub breaker: BreakerState,
+    pub funding_ref_ema: Option<f64>,
+    pub price_feed: PriceFeedState,
+    pub reputation: Vec<ProviderScore>,
+    #[serde(default)]
+    pub premium: Vec<(i64, f64)>, // (ts_ms, premium) samples, markets with a perp venue
+    #[serde(default)]
+    pub funding_acc: AccumulatorState,
+    #[serde(default)]
+    pub funding_last_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StateFile {
+    pub version: u32,
+    pub markets: BTreeMap<String, OracleState>, // by symbol
+}
+
+pub struct StateStore {
+    path: PathBuf,
+    state: Mutex<StateFile>, // also serialises file writes
+}
+
+impl StateStore {
+    /// Loads the file at `path` if there is one. State is only a warm start, so a
+    /// file that doesn't parse is logged and ignored rather than an error.
+    pub async fn open<P: Into<PathBuf>>(path: P) -> anyhow::Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let state = match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice::<StateFile>(&bytes).unwrap_or_else(|e| {
+                tracing::warn!("ignoring unreadable state file {}: {e}", path.display());
+                StateFile::default()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => StateFile::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, state: Mutex::new(state) })
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+
+    pub async fn get(&self, symbol: &str) -> Option<OracleState> {
+        self.state.lock().await.markets.get(symbol).cloned()
+    }
+
+    /// Replace `symbol`'s snapshot and rewrite the file.
+    pub async fn save(&self, symbol: &str, snapshot: OracleState) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+        state.version = STATE_VERSION;
+        state.markets.insert(symbol.to_string(), snapshot);
+        let bytes = serde_json::to_vec(&*state)?;
+        write_atomic(&self.path, &bytes).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::clock::ManualClock;
+    use crate::config::{OracleConfig, StateConfig};
+    use crate::funding::FundingEngine;
+    use crate::oracle::Oracle;
+    use crate::publishing::StdoutPublisher;
+    use crate::types::ConsensusStats;
+
+    const T: i64 = 1_700_000_000_000;
+
+    fn oracle(clock: &ManualClock) -> Oracle<StdoutPublisher> {
+        let cfg = OracleConfig { symbol: "CORN_PERP".into(), expo: -8, ..OracleConfig::default() };
+        Oracle::new(cfg, StdoutPublisher {}, vec![], FundingEngine::new(0.02, 0.005, 8 * 3600)).with_clock(Arc::new(clock.clone()))
+    }
+
+    #[tokio::test]
+    async fn saves_and_restores_with_age_checks() {
+        let clock = ManualClock::new(T);
+        let mut before = oracle(&clock);
+        let mark = IndexTick { symbol: "CORN_PERP".into(), price: 4.5, expo: -8, ts_ms: T, source: "hybrid", window_sec: 0 };
+        let stats = ConsensusStats { n_fresh: 2, n_used: 2, n_dropped: 0, spread_bps: 4, confidence: 0.9, conf: 0.01 };
+        before.price_feed.update(&mark, &stats).unwrap();
+        before.last_good_mark = Some(mark);
+        before.funding_ref_ema.update(4.4);
+        before.funding_acc.observe(0.001, T);
+        before.funding_last_rate = Some(0.0001);
+        before.risk.eval_circuit_breaker(4.5, T);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state/oracle.json");
+        StateStore::open(&path).await.unwrap().save("CORN_PERP", before.snapshot()).await.unwrap();
+        let saved = StateStore::open(&path).await.unwrap().get("CORN_PERP").await.unwrap();
+        assert_eq!(saved, before.snapshot());
+
+        // A minute later everything comes back.
+        clock.advance_ms(60_000);
+        let mut after = oracle(&clock);
+        let parts = after.restore(saved.clone(), &StateConfig::default());
+        assert_eq!(parts, ["provider scores", "last good mark", "breaker", "funding EMA", "funding interval", "last funding rate", "price feed EMA"]);
+        assert_eq!(after.last_good_mark.as_ref().map(|m| (m.price, m.source)), Some((4.5, "hybrid")));
+        assert_eq!(after.funding_ref_ema.value, Some(4.4));
+        assert_eq!(after.price_feed.state(), before.price_feed.state());
+        assert!(after.risk.eval_circuit_breaker(4.5 * 1.1, T + 60_000).tripped);
+
+        // An hour later only the provider scores are worth keeping.
+        clock.advance_ms(3_600_000);
+        let mut stale = oracle(&clock);
+        assert_eq!(stale.restore(saved, &StateConfig::default()), ["provider scores"]);
+        assert!(stale.last_good_mark.is_none() && stale.funding_ref_ema.value.is_none());
+
+        std::fs::write(&path, b"{not json").unwrap();
+        assert!(StateStore::open(&path).await.unwrap().get("CORN_PERP").await.is_none());
+    }
+}
@@ -18,12 +18,33 @@ pub struct IndexTick {
     pub window_sec: u32,       // TWAP period applied
 }
 
+#[cfg(test)]
+impl IndexTick {
+    /// Test fixture: a consensus mark for `symbol` at expo -8.
+    pub(crate) fn fixture(symbol: &str, price: f64, ts_ms: i64) -> Self {
+        Self { symbol: symbol.into(), price, expo: -8, ts_ms, source: "cfd-consensus", window_sec: 0 }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FundingUpdate {
     pub symbol: String,      // "LH-PERP"
     pub rate: f64,           // signed fraction per interval (e.g. 0.004 = 0.4%)
     pub interval_sec: u32,   // e.g. 8h
     pub ts_ms: i64,
+    #[serde(default)]
+    pub kind: FundingKind,
+    #[serde(default)]
+    pub settle_ms: i64,      // the UTC-aligned settlement this rate is for
+}
+
+/// A settled interval's rate, or the running prediction for the next settlement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FundingKind {
+    #[default]
+    Settled,
+    Predicted,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -40,6 +61,49 @@ pub struct CmfInputs {
     pub target_days: f64, // e.g., 30d constant maturity
 }
 
+// ---- Perp venue types ----
+
+/// Our perp's order book on the venue: `(price, size)` levels, best first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+    pub ts_ms: i64,
+}
+
+impl OrderBook {
+    /// Average fill price for selling (`impact_bid`) and buying (`impact_ask`)
+    /// `notional` of the quote currency. `None` when either side is too thin.
+    pub fn impact_prices(&self, notional: f64) -> Option<ImpactPrices> {
+        Some(ImpactPrices {
+            impact_bid: impact_fill(&self.bids, notional)?,
+            impact_ask: impact_fill(&self.asks, notional)?,
+            ts_ms: self.ts_ms,
+        })
+    }
+}
+
+fn impact_fill(levels: &[(f64, f64)], notional: f64) -> Option<f64> {
+    let (mut quote, mut base) = (0.0, 0.0);
+    for &(px, size) in levels {
+        let take = (notional - quote).min(px * size);
+        quote += take;
+        base += take / px;
+        if quote >= notional {
+            return Some(quote / base);
+        }
+    }
+    None
+}
+
+/// Where a trade of the configured impact notional would fill on each side.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImpactPrices {
+    pub impact_bid: f64,
+    pub impact_ask: f64,
+    pub ts_ms: i64,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct CfdTick {
     pub price: f64,
@@ -63,6 +127,17 @@ pub fn scale_by_expo(px: f64, expo: i8) -> Result<u64, &'static str> {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CfdSource { Ninjas, Owninja, Other(String) }
 
+impl CfdSource {
+    /// Stable provider tag, matching `CfdProvider::name()` for the built-ins.
+    pub fn name(&self) -> &str {
+        match self {
+            CfdSource::Ninjas => "ninjas",
+            CfdSource::Owninja => "owninja",
+            CfdSource::Other(s) => s,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CfdQuote {
     pub src: CfdSource,
@@ -71,11 +146,25 @@ pub struct CfdQuote {
 }
 
 // Optional telemetry you can publish with a tick
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct ConsensusStats {
     pub n_fresh: usize,
     pub n_used: usize,
     pub n_dropped: usize,
     pub spread_bps: u32,   // (max-min)/median in bps
     pub confidence: f32,   // 0..1
+    pub conf: f64,         // confidence band in price units: max(1.4826·MAD, weighted std dev of kept quotes)
+}
+
+/// Pyth-style price output: integers scaled by `expo`, EMA fields smoothed
+/// with inverse-conf weights (see `price_feed::PriceFeedAggregator`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceFeed {
+    pub symbol: String,
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,  // unix seconds
+    pub ema_price: i64,
+    pub ema_conf: u64,
 }